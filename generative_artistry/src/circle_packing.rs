@@ -1,19 +1,39 @@
+use std::path::Path;
+
 use nannou::prelude::*;
+use nannou::rand::rngs::StdRng;
+use nannou::rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use sdx_art_lib::canvas::Canvas;
+use sdx_art_lib::error::{ensure_at_least, ensure_non_negative, Error};
+use sdx_art_lib::interactive::{self, bump_count, Controls, Interactive, Tweak};
+use sdx_art_lib::metadata::Metadata;
+use sdx_art_lib::packing::{pack_circles, Packing};
+use sdx_art_lib::shapes::Drawable;
+use sdx_art_lib::sketch::{self, Handoff};
+use sdx_art_lib::sketchy::Sketchy;
+use sdx_art_lib::watch::{self, Watch};
 
-use sdx_art_lib::shapes::circle::Circle;
+use crate::piece::Piece;
 
-static mut LINE_WIDTH: f32 = 2.0;
-static mut MIN_RADIUS: usize = 2;
-static mut MAX_RADIUS: usize = 250;
-static mut TOTAL_CIRCLES: usize = 1000;
-static mut CREATE_CIRCLE_ATTEMPTS: usize = 500;
+static DESCRIPTOR: Handoff<CirclePackingDescriptor> = Handoff::new();
+static WATCH: Handoff<Watch> = Handoff::new();
 
+#[derive(Serialize, Deserialize)]
 pub struct CirclePackingDescriptor {
+    width: u32,
+    height: u32,
     line_width: f32,
     min_radius: usize,
     max_radius: usize,
     total_circles: usize,
     create_circle_attempts: usize,
+    seed: u64,
+    sketchy: Option<Sketchy>,
+    /// Previously saved circles, drawn instead of packing new ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    packing: Option<Packing>,
 }
 
 impl CirclePackingDescriptor {
@@ -23,139 +43,281 @@ impl CirclePackingDescriptor {
         max_radius: usize,
         total_circles: usize,
         create_circle_attempts: usize,
+        seed: u64,
+        sketchy: Option<Sketchy>,
     ) -> CirclePackingDescriptor {
         CirclePackingDescriptor {
+            width: 1024,
+            height: 768,
             line_width,
             min_radius,
             max_radius,
             total_circles,
             create_circle_attempts,
+            seed,
+            sketchy,
+            packing: None,
         }
     }
 
-    fn from_statics() -> CirclePackingDescriptor {
-        let (line_width, min_radius, max_radius, total_circles, create_circle_attempts) = unsafe {
-            (
-                LINE_WIDTH,
-                MIN_RADIUS,
-                MAX_RADIUS,
-                TOTAL_CIRCLES,
-                CREATE_CIRCLE_ATTEMPTS,
-            )
-        };
+    /// Sets the canvas size, 1024x768 by default.
+    pub fn with_size(self, width: u32, height: u32) -> CirclePackingDescriptor {
+        CirclePackingDescriptor {
+            width,
+            height,
+            ..self
+        }
+    }
 
+    pub fn with_packing(self, packing: Packing) -> CirclePackingDescriptor {
         CirclePackingDescriptor {
-            line_width,
-            min_radius,
-            max_radius,
-            total_circles,
-            create_circle_attempts,
+            packing: Some(packing),
+            ..self
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("width", self.width as usize, 1)?;
+        ensure_at_least("height", self.height as usize, 1)?;
+        ensure_non_negative("line width", self.line_width)?;
+        ensure_at_least("max radius", self.max_radius, 1)?;
+        if self.min_radius > self.max_radius {
+            return Err(Error::invalid(
+                "min radius",
+                format!(
+                    "must not be greater than the max radius, got {} > {}",
+                    self.min_radius, self.max_radius
+                ),
+            ));
+        }
+        ensure_at_least("create circle attempts", self.create_circle_attempts, 1)?;
+        if let Some(packing) = &self.packing {
+            packing.circles.iter().try_for_each(Drawable::validate)?;
+        }
+        self.sketchy.as_ref().map_or(Ok(()), Sketchy::validate)
+    }
+
+    fn from_statics() -> CirclePackingDescriptor {
+        DESCRIPTOR
+            .take()
+            .expect("circle packing descriptor must be set before the app starts")
+    }
+
+    /// Uses `seed` for the packing and the sketchy strokes, dropping a loaded packing.
+    fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        if let Some(sketchy) = &mut self.sketchy {
+            sketchy.seed = seed;
+        }
+        self.packing = None;
+    }
+
+    /// Bumps the circle count, the max or the min radius, dropping a loaded packing.
+    fn bump(&mut self, parameter: usize, direction: i32) {
+        match parameter {
+            0 => self.total_circles = bump_count(self.total_circles, direction * 10, 1, usize::MAX),
+            1 => {
+                self.max_radius = bump_count(
+                    self.max_radius,
+                    direction * 2,
+                    self.min_radius.max(1),
+                    usize::MAX,
+                )
+            }
+            2 => self.min_radius = bump_count(self.min_radius, direction, 0, self.max_radius),
+            _ => return,
+        }
+        self.packing = None;
+    }
+
+    fn tweaks(&self) -> Vec<Tweak> {
+        vec![
+            Tweak::new("circles", self.total_circles),
+            Tweak::new("max radius", self.max_radius),
+            Tweak::new("min radius", self.min_radius),
+            Tweak::fixed("seed", self.seed),
+        ]
+    }
+
+    /// The loaded circles, or new ones packed into a canvas of the descriptor size.
+    pub fn pack(&self) -> Packing {
+        if let Some(packing) = &self.packing {
+            return packing.clone();
         }
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        Packing {
+            circles: pack_circles(
+                &Rect::from_w_h(self.width as f32, self.height as f32),
+                self.min_radius,
+                self.max_radius,
+                self.total_circles,
+                self.create_circle_attempts,
+                &mut rng,
+            ),
+        }
+    }
+}
+
+impl Piece for CirclePackingDescriptor {
+    fn sketch(&self) -> &'static str {
+        "circle_packing"
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
     }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        CirclePackingDescriptor::validate(self)
+    }
+
+    fn render(&self, canvas: &dyn Canvas, rect: Rect) -> Result<(), Error> {
+        render(canvas, rect, self);
+        Ok(())
+    }
+}
+
+pub fn present(descriptor: CirclePackingDescriptor) -> Result<(), Error> {
+    run(descriptor, None)
+}
+
+/// Shows the descriptor read from `path`, packing again in the same window whenever the file is
+/// saved. `descriptor` is written to the file first if it doesn't exist yet.
+pub fn watch(descriptor: CirclePackingDescriptor, path: &Path) -> Result<(), Error> {
+    run(
+        watch::load_or_create(path, descriptor)?,
+        Some(Watch::new(path)),
+    )
 }
 
-pub fn present(descriptor: CirclePackingDescriptor) {
-    unsafe {
-        LINE_WIDTH = descriptor.line_width;
-        MIN_RADIUS = descriptor.min_radius;
-        MAX_RADIUS = descriptor.max_radius;
-        TOTAL_CIRCLES = descriptor.total_circles;
-        CREATE_CIRCLE_ATTEMPTS = descriptor.create_circle_attempts;
+fn run(descriptor: CirclePackingDescriptor, watch: Option<Watch>) -> Result<(), Error> {
+    descriptor.validate()?;
+
+    let (width, height) = (descriptor.width, descriptor.height);
+    let loop_mode = if watch.is_some() {
+        // Keeps updating, so changes of the file are noticed.
+        LoopMode::RefreshSync
+    } else {
+        LoopMode::Wait
     };
+    DESCRIPTOR.put(descriptor);
+    WATCH.put(watch);
 
     nannou::app(model)
-        .loop_mode(LoopMode::loop_ntimes(1))
+        .loop_mode(loop_mode)
         .update(update)
+        .exit(interactive::exit)
+        .size(width, height)
         .run();
+
+    Ok(())
 }
 
 struct Model {
-    _window: window::Id,
+    _window: Option<window::Id>,
     descriptor: CirclePackingDescriptor,
+    /// Packed once per change of the descriptor rather than on every redraw.
+    packing: Packing,
+    controls: Controls,
+    watch: Option<Watch>,
 }
 
 fn model(app: &App) -> Model {
-    let _window = app.new_window().view(view).build().unwrap();
+    let _window = sketch::window(
+        app,
+        app.new_window()
+            .title(interactive::title("circle_packing"))
+            .resizable(false)
+            .view(view)
+            .key_pressed(interactive::key_pressed::<Model>)
+            .mouse_pressed(interactive::mouse_pressed::<Model>)
+            .build(),
+    );
+
+    let descriptor = CirclePackingDescriptor::from_statics();
     Model {
         _window,
-        descriptor: CirclePackingDescriptor::from_statics(),
+        packing: descriptor.pack(),
+        descriptor,
+        controls: Controls::default(),
+        watch: WATCH.take(),
     }
 }
 
-fn update(_app: &App, _model: &mut Model, _update: Update) {}
-
-fn view(app: &App, model: &Model, frame: Frame) {
-    let draw = app.draw();
-    let window = app.window_rect();
-
-    draw.background().color(WHITE);
-
-    let mut circles = vec![];
-
-    for _ in 0..model.descriptor.total_circles {
-        if let Some(circle) = try_create_circle(&window, &circles, model) {
-            circle.draw(&draw);
-            circles.push(circle);
+fn update(app: &App, model: &mut Model, _update: Update) {
+    if let Some(watch) = &mut model.watch {
+        if let Some(descriptor) = watch.reload_or_report(CirclePackingDescriptor::validate) {
+            if descriptor.size() != model.descriptor.size() {
+                app.main_window()
+                    .set_inner_size_points(descriptor.width as f32, descriptor.height as f32);
+            }
+            model.packing = descriptor.pack();
+            model.descriptor = descriptor;
         }
     }
 
-    draw.to_frame(app, &frame).unwrap();
+    interactive::update(app, model);
 }
 
-fn try_position_circle(window: &Rect, circles: &[Circle], model: &Model) -> Option<Circle> {
-    for _ in 0..model.descriptor.create_circle_attempts {
-        let circle = Circle::new(model.descriptor.min_radius as f32)
-            .with_weight(model.descriptor.line_width)
-            .with_x_y(
-                random_range::<f32>(window.top_left().x, window.bottom_right().x),
-                random_range::<f32>(window.top_left().y, window.bottom_right().y),
-            );
-
-        if !circle_has_collision(&circle, circles, window) {
-            return Some(circle);
-        }
+impl Interactive for Model {
+    fn reseed(&mut self, _app: &App) {
+        self.descriptor.reseed(random());
+        self.packing = self.descriptor.pack();
     }
 
-    None
-}
-
-fn try_create_circle(window: &Rect, circles: &[Circle], model: &Model) -> Option<Circle> {
-    let mut circle = try_position_circle(window, circles, model)?;
+    fn bump(&mut self, parameter: usize, direction: i32) {
+        self.descriptor.bump(parameter, direction);
+        self.packing = self.descriptor.pack();
+    }
 
-    for radius in model.descriptor.min_radius..model.descriptor.max_radius {
-        circle = circle.with_radius((radius) as f32);
-        if circle_has_collision(&circle, circles, window) {
-            circle = circle.with_radius((radius - 1) as f32);
+    fn tweaks(&self) -> Vec<Tweak> {
+        self.descriptor.tweaks()
+    }
 
-            break;
-        }
+    fn metadata(&self, _app: &App) -> Metadata {
+        self.descriptor.metadata()
     }
 
-    Some(circle)
+    fn controls(&self) -> &Controls {
+        &self.controls
+    }
 }
 
-fn circle_has_collision(circle: &Circle, circles: &[Circle], window: &Rect) -> bool {
-    for other_circle in circles.iter() {
-        let a = circle.radius + other_circle.radius;
-        let x = circle.pos.x - other_circle.pos.x;
-        let y = circle.pos.y - other_circle.pos.y;
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    let rect = app.window_rect();
+
+    draw_circles(&draw, rect, &model.descriptor, &model.packing);
+    interactive::draw_controls(app, &draw, rect, model);
 
-        if a >= ((x * x) + (y * y)).sqrt() {
-            return true;
-        }
-    }
+    sketch::show(app, &draw, &frame);
+}
 
-    if circle.pos.x + circle.radius >= window.top_right().x
-        || circle.pos.x - circle.radius <= window.top_left().x
-    {
-        return true;
-    }
+/// Draws the outlines of the packed circles. The packing is relative to the canvas center, so
+/// `rect` is expected to be of the descriptor size.
+pub fn render(canvas: &dyn Canvas, rect: Rect, descriptor: &CirclePackingDescriptor) {
+    draw_circles(canvas, rect, descriptor, &descriptor.pack());
+}
 
-    if circle.pos.y + circle.radius >= window.top_left().y
-        || circle.pos.y - circle.radius <= window.bottom_left().y
-    {
-        return true;
-    }
+fn draw_circles(
+    canvas: &dyn Canvas,
+    rect: Rect,
+    descriptor: &CirclePackingDescriptor,
+    packing: &Packing,
+) {
+    Sketchy::wrap(descriptor.sketchy, canvas, |canvas| {
+        canvas.fill_background(Rgba::new(1.0, 1.0, 1.0, 1.0));
 
-    false
+        for circle in &packing.circles {
+            circle
+                .with_xy(circle.pos + rect.xy())
+                .with_weight(descriptor.line_width)
+                .draw(canvas);
+        }
+    });
 }
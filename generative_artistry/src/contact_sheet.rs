@@ -0,0 +1,154 @@
+use nannou::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use sdx_art_lib::canvas::Canvas;
+use sdx_art_lib::contact_sheet::{self, Entry, Layout};
+use sdx_art_lib::error::{ensure_at_least, Error};
+use sdx_art_lib::metadata::Metadata;
+
+use crate::piece::{self, Piece};
+
+/// Several pieces laid out in a grid on one canvas, e.g. to pick one out of many variants.
+///
+/// The pieces are kept as the metadata they're rebuilt from, so sheets can be exported and
+/// reproduced like any other piece.
+#[derive(Serialize, Deserialize)]
+pub struct ContactSheet {
+    layout: Layout,
+    pieces: Vec<Metadata>,
+    /// One caption per piece, or none at all.
+    #[serde(default)]
+    captions: Vec<String>,
+}
+
+impl ContactSheet {
+    pub fn new(pieces: Vec<Metadata>, layout: Layout) -> ContactSheet {
+        ContactSheet {
+            layout,
+            pieces,
+            captions: vec![],
+        }
+    }
+
+    /// Captions are drawn with the caption size of the layout.
+    pub fn with_captions(self, captions: Vec<String>) -> ContactSheet {
+        ContactSheet { captions, ..self }
+    }
+
+    /// Captions every piece with the parameters it doesn't share with all other pieces of its
+    /// sketch, and with its seed if it differs or nothing else does.
+    pub fn with_parameter_captions(self) -> ContactSheet {
+        let captions = parameter_captions(&self.pieces);
+
+        self.with_captions(captions)
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        self.layout.validate()?;
+        ensure_at_least("pieces", self.pieces.len(), 1)?;
+        if !self.captions.is_empty() && self.captions.len() != self.pieces.len() {
+            return Err(Error::invalid(
+                "captions",
+                format!(
+                    "expected one per piece, got {} for {} pieces",
+                    self.captions.len(),
+                    self.pieces.len()
+                ),
+            ));
+        }
+
+        self.pieces
+            .iter()
+            .try_for_each(|metadata| piece::from_metadata(metadata).map(drop))
+    }
+}
+
+impl Piece for ContactSheet {
+    fn sketch(&self) -> &'static str {
+        "contact_sheet"
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.layout.size(self.pieces.len())
+    }
+
+    /// Sheets aren't random, their pieces have seeds of their own.
+    fn seed(&self) -> u64 {
+        0
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        ContactSheet::validate(self)
+    }
+
+    fn render(&self, canvas: &dyn Canvas, rect: Rect) -> Result<(), Error> {
+        let entries = self
+            .pieces
+            .iter()
+            .enumerate()
+            .map(|(i, metadata)| {
+                let piece = piece::from_metadata(metadata)?;
+                let (width, height) = piece.size();
+
+                Ok(Entry {
+                    rect: Rect::from_w_h(width as f32, height as f32),
+                    caption: self.captions.get(i).cloned(),
+                    render: Box::new(move |canvas, rect| piece.render(canvas, rect)),
+                })
+            })
+            .collect::<Result<Vec<Entry>, Error>>()?;
+
+        contact_sheet::draw(canvas, rect, &self.layout, &entries)
+    }
+}
+
+fn parameter_captions(pieces: &[Metadata]) -> Vec<String> {
+    let mixed_sketches = pieces.iter().any(|other| other.sketch != pieces[0].sketch);
+
+    pieces
+        .iter()
+        .map(|metadata| {
+            // Parameters are only compared between pieces of the same sketch.
+            let peers: Vec<&Metadata> = pieces
+                .iter()
+                .filter(|other| other.sketch == metadata.sketch)
+                .collect();
+            let differs = |key: &str| {
+                peers
+                    .iter()
+                    .any(|other| other.parameters.get(key) != metadata.parameters.get(key))
+            };
+
+            let mut parts = vec![];
+            if mixed_sketches {
+                parts.push(metadata.sketch.clone());
+            }
+            if let Some(parameters) = metadata.parameters.as_object() {
+                for (key, value) in parameters {
+                    if key != "seed" && differs(key) {
+                        parts.push(format!("{}={}", key, caption_value(value)));
+                    }
+                }
+            }
+            if parts.len() == usize::from(mixed_sketches)
+                || peers.iter().any(|other| other.seed != metadata.seed)
+            {
+                parts.push(format!("seed={}", metadata.seed));
+            }
+
+            parts.join(" ")
+        })
+        .collect()
+}
+
+/// Parameter values as short as possible, e.g. `0.7` for an `f32` that is `0.699999988` as JSON.
+fn caption_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Number(n) if n.is_f64() => {
+            let n = n.as_f64().unwrap_or_default();
+            ((n * 1e6).round() / 1e6).to_string()
+        }
+        _ => value.to_string(),
+    }
+}
@@ -0,0 +1,134 @@
+use nannou::prelude::*;
+use nannou::rand::rngs::StdRng;
+use nannou::rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use sdx_art_lib::canvas::Canvas;
+use sdx_art_lib::error::{ensure_at_least, ensure_non_negative, ensure_positive, Error};
+use sdx_art_lib::shapes::rectangle::Rectangle;
+use sdx_art_lib::shapes::Drawable;
+
+use crate::piece::Piece;
+
+/// How fast the disarray grows from the top row (0) to the bottom row (1).
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Falloff {
+    Linear,
+    Quadratic,
+    Exponential,
+}
+
+impl Falloff {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Falloff::Linear => t,
+            Falloff::Quadratic => t * t,
+            Falloff::Exponential => (2f32.powf(6.0 * t) - 1.0) / 63.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CubicDisarrayDescriptor {
+    size: u32,
+    square_size: f32,
+    displacement: f32,
+    rotation: f32,
+    falloff: Falloff,
+    line_width: f32,
+    seed: u64,
+}
+
+impl CubicDisarrayDescriptor {
+    pub fn new(
+        size: u32,
+        square_size: f32,
+        displacement: f32,
+        rotation: f32,
+        falloff: Falloff,
+        line_width: f32,
+        seed: u64,
+    ) -> CubicDisarrayDescriptor {
+        CubicDisarrayDescriptor {
+            size,
+            square_size,
+            displacement,
+            rotation,
+            falloff,
+            line_width,
+            seed,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("size", self.size as usize, 1)?;
+        ensure_positive("square size", self.square_size)?;
+        ensure_non_negative("displacement", self.displacement)?;
+        ensure_non_negative("rotation", self.rotation)?;
+        ensure_non_negative("line width", self.line_width)
+    }
+}
+
+impl Piece for CubicDisarrayDescriptor {
+    fn sketch(&self) -> &'static str {
+        "cubic_disarray"
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.size, self.size)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        CubicDisarrayDescriptor::validate(self)
+    }
+
+    fn render(&self, canvas: &dyn Canvas, rect: Rect) -> Result<(), Error> {
+        render(canvas, rect, self);
+        Ok(())
+    }
+}
+
+/// Draws a grid of squares which get more and more rotated and displaced towards the bottom.
+pub fn render(canvas: &dyn Canvas, rect: Rect, descriptor: &CubicDisarrayDescriptor) {
+    let mut rng = StdRng::seed_from_u64(descriptor.seed);
+
+    canvas.fill_background(Rgba::new(1.0, 1.0, 1.0, 1.0));
+
+    let square_size = descriptor.square_size.max(1.0);
+    let columns = (rect.w() / square_size).floor().max(1.0) as usize;
+    let rows = (rect.h() / square_size).floor().max(1.0) as usize;
+    let offset = vec2(
+        (rect.w() - columns as f32 * square_size) / 2.0,
+        (rect.h() - rows as f32 * square_size) / 2.0,
+    );
+
+    for row in 0..rows {
+        let disarray = descriptor
+            .falloff
+            .apply(row as f32 / (rows - 1).max(1) as f32);
+
+        for column in 0..columns {
+            let plus_or_minus = if rng.gen::<bool>() { 1.0 } else { -1.0 };
+            let rotation =
+                deg_to_rad(plus_or_minus * rng.gen::<f32>() * descriptor.rotation * disarray);
+            let displacement =
+                plus_or_minus * rng.gen::<f32>() * descriptor.displacement * disarray;
+
+            Rectangle::square(square_size)
+                .with_weight(descriptor.line_width)
+                .with_rotation(rotation)
+                .with_x_y(
+                    rect.left() + offset.x + (column as f32 + 0.5) * square_size + displacement,
+                    rect.top() - offset.y - (row as f32 + 0.5) * square_size,
+                )
+                .draw(canvas);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use nannou::prelude::*;
+use nannou::rand::rngs::StdRng;
+use nannou::rand::seq::SliceRandom;
+use nannou::rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use sdx_art_lib::canvas::Canvas;
+use sdx_art_lib::error::{
+    ensure_at_least, ensure_at_most, ensure_non_negative, ensure_unit, Error,
+};
+use sdx_art_lib::grid::rectangular_cells;
+use sdx_art_lib::shapes::rectangle::Rectangle;
+use sdx_art_lib::shapes::Drawable;
+use sdx_art_lib::subdivision::nested_rects;
+
+use crate::piece::Piece;
+
+/// Deepest nesting allowed, the squares being nested recursively. Squares this close together
+/// blur into a filled tile long before.
+const MAX_DEPTH: usize = 1000;
+
+#[derive(Serialize, Deserialize)]
+pub struct HypnoticSquaresDescriptor {
+    size: u32,
+    /// Number of tiles per row and column.
+    tiles: usize,
+    /// Number of squares nested into the outermost one.
+    depth: usize,
+    /// Size of the innermost square.
+    min_size: f32,
+    /// How far the nested squares drift towards the tile edge, from 0 to 1.
+    offset: f32,
+    line_width: f32,
+    seed: u64,
+}
+
+impl HypnoticSquaresDescriptor {
+    pub fn new(
+        size: u32,
+        tiles: usize,
+        depth: usize,
+        min_size: f32,
+        offset: f32,
+        line_width: f32,
+        seed: u64,
+    ) -> HypnoticSquaresDescriptor {
+        HypnoticSquaresDescriptor {
+            size,
+            tiles,
+            depth,
+            min_size,
+            offset,
+            line_width,
+            seed,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("size", self.size as usize, 1)?;
+        ensure_at_least("tiles", self.tiles, 1)?;
+        ensure_at_least("depth", self.depth, 1)?;
+        ensure_at_most("depth", self.depth, MAX_DEPTH)?;
+        ensure_non_negative("min size", self.min_size)?;
+        ensure_unit("offset", self.offset)?;
+        ensure_non_negative("line width", self.line_width)
+    }
+}
+
+impl Piece for HypnoticSquaresDescriptor {
+    fn sketch(&self) -> &'static str {
+        "hypnotic_squares"
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.size, self.size)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        HypnoticSquaresDescriptor::validate(self)
+    }
+
+    fn render(&self, canvas: &dyn Canvas, rect: Rect) -> Result<(), Error> {
+        render(canvas, rect, self);
+        Ok(())
+    }
+}
+
+/// Draws a square per tile with smaller and smaller squares nested into it, all drifting
+/// towards a random direction.
+pub fn render(canvas: &dyn Canvas, rect: Rect, descriptor: &HypnoticSquaresDescriptor) {
+    let mut rng = StdRng::seed_from_u64(descriptor.seed);
+    let directions = [-1.0, 0.0, 1.0];
+
+    canvas.fill_background(Rgba::new(1.0, 1.0, 1.0, 1.0));
+
+    let margin = rect.w().min(rect.h()) / 20.0;
+    let inner = Rect::from_x_y_w_h(
+        rect.x(),
+        rect.y(),
+        rect.w() - margin * 2.0,
+        rect.h() - margin * 2.0,
+    );
+    let tiles = descriptor.tiles.max(1);
+
+    for tile in rectangular_cells(inner, tiles, tiles) {
+        let direction = vec2(
+            *directions.choose(&mut rng).unwrap(),
+            *directions.choose(&mut rng).unwrap(),
+        );
+
+        // Leave a gap between neighbouring tiles.
+        let tile = tile.pad(descriptor.line_width * 2.0);
+
+        for square in nested_rects(
+            tile,
+            descriptor.depth,
+            descriptor.min_size,
+            direction,
+            descriptor.offset.clamp(0.0, 1.0),
+        ) {
+            Rectangle::new(square.w(), square.h())
+                .with_weight(descriptor.line_width)
+                .with_x_y(square.x(), square.y())
+                .draw(canvas);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use nannou::prelude::*;
+use nannou::rand::rngs::StdRng;
+use nannou::rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use sdx_art_lib::canvas::Canvas;
+use sdx_art_lib::error::{ensure_at_least, ensure_non_negative, Error};
+use sdx_art_lib::shapes::polygon::Polygon;
+use sdx_art_lib::shapes::Drawable;
+use sdx_art_lib::stroke::StrokeStyle;
+
+use crate::piece::Piece;
+
+#[derive(Serialize, Deserialize)]
+pub struct JoyDivisionDescriptor {
+    size: u32,
+    lines: usize,
+    points_per_line: usize,
+    amplitude: f32,
+    distortion: f32,
+    line_width: f32,
+    seed: u64,
+}
+
+impl JoyDivisionDescriptor {
+    pub fn new(
+        size: u32,
+        lines: usize,
+        points_per_line: usize,
+        amplitude: f32,
+        distortion: f32,
+        line_width: f32,
+        seed: u64,
+    ) -> JoyDivisionDescriptor {
+        JoyDivisionDescriptor {
+            size,
+            lines,
+            points_per_line,
+            amplitude,
+            distortion,
+            line_width,
+            seed,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("size", self.size as usize, 1)?;
+        ensure_at_least("lines", self.lines, 1)?;
+        ensure_at_least("points per line", self.points_per_line, 1)?;
+        ensure_non_negative("amplitude", self.amplitude)?;
+        ensure_non_negative("distortion", self.distortion)?;
+        ensure_non_negative("line width", self.line_width)
+    }
+}
+
+impl Piece for JoyDivisionDescriptor {
+    fn sketch(&self) -> &'static str {
+        "joy_division"
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.size, self.size)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        JoyDivisionDescriptor::validate(self)
+    }
+
+    fn render(&self, canvas: &dyn Canvas, rect: Rect) -> Result<(), Error> {
+        render(canvas, rect, self);
+        Ok(())
+    }
+}
+
+/// Draws the ridges from the back to the front. Each ridge is first filled with the background
+/// color, hiding the parts of the ridges behind it, and then stroked.
+pub fn render(canvas: &dyn Canvas, rect: Rect, descriptor: &JoyDivisionDescriptor) {
+    let mut rng = StdRng::seed_from_u64(descriptor.seed);
+    let background = Rgba::new(1.0, 1.0, 1.0, 1.0);
+    let stroke = StrokeStyle::new(Rgba::new(0.0, 0.0, 0.0, 1.0), descriptor.line_width);
+
+    canvas.fill_background(background);
+
+    let margin = rect.w().min(rect.h()) / 8.0;
+    let inner = Rect::from_x_y_w_h(
+        rect.x(),
+        rect.y(),
+        rect.w() - margin * 2.0,
+        rect.h() - margin * 2.0,
+    );
+    let lines = descriptor.lines.max(1);
+    let points = descriptor.points_per_line.max(1);
+    let line_step = inner.h() / lines as f32;
+    let point_step = inner.w() / points as f32;
+
+    for line in 1..=lines {
+        let base = inner.top() - line as f32 * line_step;
+
+        let ridge = (0..=points)
+            .map(|i| {
+                let x = inner.left() + i as f32 * point_step;
+                let center_distance = (x - inner.x()).abs() / (inner.w() / 2.0);
+                let weight = (1.0 - center_distance).max(0.0).powf(descriptor.distortion);
+
+                pt2(x, base + rng.gen::<f32>() * descriptor.amplitude * weight)
+            })
+            .collect::<Vec<Point2>>();
+
+        let mut area = ridge.clone();
+        area.push(pt2(inner.right(), base));
+        area.push(pt2(inner.left(), base));
+
+        Polygon::new(area)
+            .with_fill(background)
+            .without_stroke()
+            .draw(canvas);
+
+        canvas.stroke_path(&ridge, &stroke);
+    }
+}
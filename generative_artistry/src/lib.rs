@@ -1,2 +1,11 @@
 pub mod circle_packing;
+pub mod contact_sheet;
+pub mod cubic_disarray;
+pub mod hypnotic_squares;
+pub mod joy_division;
+pub mod mondrian;
+pub mod piece;
 pub mod tiled_lines;
+pub mod triangular_mesh;
+pub mod un_deux_trois;
+pub mod unlikely_squares;
@@ -0,0 +1,119 @@
+use nannou::prelude::*;
+use nannou::rand::rngs::StdRng;
+use nannou::rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use sdx_art_lib::canvas::Canvas;
+use sdx_art_lib::error::{
+    ensure_at_least, ensure_non_negative, ensure_positive, ensure_unit, Error,
+};
+use sdx_art_lib::palette::Palette;
+use sdx_art_lib::shapes::rectangle::Rectangle;
+use sdx_art_lib::shapes::Drawable;
+use sdx_art_lib::stroke::StrokeStyle;
+use sdx_art_lib::subdivision::{Axis, Bsp};
+
+use crate::piece::Piece;
+
+#[derive(Serialize, Deserialize)]
+pub struct MondrianDescriptor {
+    size: u32,
+    /// Distance between the lines rectangles may be split along.
+    step: f32,
+    /// Chance of a rectangle being split by a line crossing it.
+    split_chance: f32,
+    /// Number of rectangles filled with a primary color.
+    colored: usize,
+    line_width: f32,
+    seed: u64,
+}
+
+impl MondrianDescriptor {
+    pub fn new(
+        size: u32,
+        step: f32,
+        split_chance: f32,
+        colored: usize,
+        line_width: f32,
+        seed: u64,
+    ) -> MondrianDescriptor {
+        MondrianDescriptor {
+            size,
+            step,
+            split_chance,
+            colored,
+            line_width,
+            seed,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("size", self.size as usize, 1)?;
+        ensure_positive("step", self.step)?;
+        ensure_unit("split chance", self.split_chance)?;
+        ensure_non_negative("line width", self.line_width)
+    }
+}
+
+impl Piece for MondrianDescriptor {
+    fn sketch(&self) -> &'static str {
+        "mondrian"
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.size, self.size)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        MondrianDescriptor::validate(self)
+    }
+
+    fn render(&self, canvas: &dyn Canvas, rect: Rect) -> Result<(), Error> {
+        render(canvas, rect, self);
+        Ok(())
+    }
+}
+
+/// Splits the canvas along evenly spaced horizontal and vertical lines, each rectangle crossed
+/// by a line having a chance to be split, then fills some of the rectangles with primary colors.
+pub fn render(canvas: &dyn Canvas, rect: Rect, descriptor: &MondrianDescriptor) {
+    let mut rng = StdRng::seed_from_u64(descriptor.seed);
+    let white = Rgba::new(0.95, 0.95, 0.92, 1.0);
+    let border = StrokeStyle::new(Rgba::new(0.0, 0.0, 0.0, 1.0), descriptor.line_width);
+
+    canvas.fill_background(white);
+
+    let step = descriptor.step.max(1.0);
+    let chance = descriptor.split_chance.clamp(0.0, 1.0);
+    let mut bsp = Bsp::new(rect);
+
+    let mut offset = step;
+    while offset < rect.w().max(rect.h()) {
+        bsp.split_crossing(Axis::Horizontal, rect.top() - offset, &mut |_| {
+            rng.gen_bool(chance as f64)
+        });
+        bsp.split_crossing(Axis::Vertical, rect.left() + offset, &mut |_| {
+            rng.gen_bool(chance as f64)
+        });
+        offset += step;
+    }
+
+    let leaves = bsp.leaves();
+    let mut fills = vec![white; leaves.len()];
+    for _ in 0..descriptor.colored {
+        let i = rng.gen_range(0..leaves.len());
+        fills[i] = Palette::Primary.pick(&mut rng);
+    }
+
+    for (leaf, fill) in leaves.iter().zip(fills) {
+        Rectangle::new(leaf.w(), leaf.h())
+            .with_x_y(leaf.x(), leaf.y())
+            .with_fill(fill)
+            .with_stroke(border.clone())
+            .draw(canvas);
+    }
+}
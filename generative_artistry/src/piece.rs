@@ -0,0 +1,213 @@
+use std::path::Path;
+
+use nannou::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use sdx_art_lib::canvas::raster::RasterCanvas;
+use sdx_art_lib::canvas::svg::SvgCanvas;
+use sdx_art_lib::canvas::Canvas;
+use sdx_art_lib::error::Error;
+use sdx_art_lib::format;
+use sdx_art_lib::metadata::Metadata;
+use sdx_art_lib::scene::Scene;
+use sdx_art_lib::sketch::{self, Handoff};
+
+use crate::circle_packing::CirclePackingDescriptor;
+use crate::contact_sheet::ContactSheet;
+use crate::cubic_disarray::CubicDisarrayDescriptor;
+use crate::hypnotic_squares::HypnoticSquaresDescriptor;
+use crate::joy_division::JoyDivisionDescriptor;
+use crate::mondrian::MondrianDescriptor;
+use crate::tiled_lines::TiledLinesDescriptor;
+use crate::triangular_mesh::TriangularMeshDescriptor;
+use crate::un_deux_trois::UnDeuxTroisDescriptor;
+use crate::unlikely_squares::UnlikelySquaresDescriptor;
+
+/// Version recorded in the metadata of exports.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+static PIECE: Handoff<Box<dyn Piece>> = Handoff::new();
+
+/// Descriptor parameters as JSON, implemented for everything serializable.
+pub trait Parameters {
+    fn parameters(&self) -> serde_json::Value;
+}
+
+impl<T: Serialize> Parameters for T {
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("descriptors are always serializable")
+    }
+}
+
+/// A sketch rendering the same every time from its descriptor, so it can be exported and
+/// reproduced from the metadata of the export.
+pub trait Piece: Parameters + Send {
+    /// Name of the sketch, as used by the CLI subcommand.
+    fn sketch(&self) -> &'static str;
+
+    fn size(&self) -> (u32, u32);
+
+    fn seed(&self) -> u64;
+
+    fn validate(&self) -> Result<(), Error>;
+
+    fn render(&self, canvas: &dyn Canvas, rect: Rect) -> Result<(), Error>;
+
+    /// Describes a render of the piece happening now.
+    fn metadata(&self) -> Metadata {
+        Metadata::new(
+            self.sketch(),
+            self.parameters(),
+            self.seed(),
+            VERSION,
+            self.size(),
+        )
+    }
+}
+
+impl Piece for Scene {
+    fn sketch(&self) -> &'static str {
+        "scene"
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        Scene::validate(self)
+    }
+
+    /// Renders the scene as it looks before any animation.
+    fn render(&self, canvas: &dyn Canvas, rect: Rect) -> Result<(), Error> {
+        self.compose(rect)?.draw(canvas);
+        Ok(())
+    }
+}
+
+/// Reconstructs the piece an export was rendered from.
+pub fn from_metadata(metadata: &Metadata) -> Result<Box<dyn Piece>, Error> {
+    fn parse<P: Piece + DeserializeOwned + 'static>(
+        metadata: &Metadata,
+    ) -> Result<Box<dyn Piece>, Error> {
+        let piece: P =
+            serde_json::from_value(metadata.parameters.clone()).map_err(|e| Error::Parse {
+                format: "JSON",
+                message: e.to_string(),
+            })?;
+        piece.validate()?;
+
+        Ok(Box::new(piece))
+    }
+
+    match metadata.sketch.as_str() {
+        "circle_packing" => parse::<CirclePackingDescriptor>(metadata),
+        "contact_sheet" => parse::<ContactSheet>(metadata),
+        "cubic_disarray" => parse::<CubicDisarrayDescriptor>(metadata),
+        "hypnotic_squares" => parse::<HypnoticSquaresDescriptor>(metadata),
+        "joy_division" => parse::<JoyDivisionDescriptor>(metadata),
+        "mondrian" => parse::<MondrianDescriptor>(metadata),
+        "tiled_lines" => parse::<TiledLinesDescriptor>(metadata),
+        "triangular_mesh" => parse::<TriangularMeshDescriptor>(metadata),
+        "un_deux_trois" => parse::<UnDeuxTroisDescriptor>(metadata),
+        "unlikely_squares" => parse::<UnlikelySquaresDescriptor>(metadata),
+        "scene" => parse::<Scene>(metadata),
+        sketch => Err(Error::invalid(
+            "sketch",
+            format!("`{}` can't be reproduced", sketch),
+        )),
+    }
+}
+
+/// Exports `piece` as SVG or PNG depending on the extension of `path`, embedding its metadata
+/// and writing it to a JSON sidecar next to the export once the export itself is written.
+///
+/// Both formats are rendered off-screen, so no window is opened.
+pub fn export(piece: Box<dyn Piece>, path: &Path) -> Result<(), Error> {
+    piece.validate()?;
+
+    let metadata = piece.metadata();
+    let (width, height) = piece.size();
+    let rect = Rect::from_w_h(width as f32, height as f32);
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("svg") => {
+            let canvas = SvgCanvas::new(rect);
+            piece.render(&canvas, canvas.rect())?;
+            format::write_atomically(path, canvas.to_svg_with_metadata(&metadata.to_json()))?;
+        }
+        Some("png") => {
+            let canvas = RasterCanvas::new(rect);
+            piece.render(&canvas, canvas.rect())?;
+            metadata.save_png(path, &canvas.to_png()?)?;
+        }
+        _ => {
+            return Err(Error::invalid(
+                "export",
+                format!("expected a .png or .svg file, got {}", path.display()),
+            ))
+        }
+    }
+
+    metadata.save_sidecar(path)
+}
+
+/// Shows `piece` in a window, rendering it once.
+///
+/// Sketches without controls of their own are presented with this rather than running an app
+/// of their own.
+pub fn present<P: Piece + 'static>(piece: P) -> Result<(), Error> {
+    present_boxed(Box::new(piece))
+}
+
+/// Like `present`, for pieces reconstructed with `from_metadata`.
+pub fn present_boxed(piece: Box<dyn Piece>) -> Result<(), Error> {
+    piece.validate()?;
+
+    let (width, height) = piece.size();
+    PIECE.put(piece);
+
+    nannou::app(model)
+        .loop_mode(LoopMode::loop_ntimes(1))
+        .update(update)
+        .size(width, height)
+        .run();
+
+    Ok(())
+}
+
+struct Model {
+    _window: Option<window::Id>,
+    piece: Box<dyn Piece>,
+}
+
+fn model(app: &App) -> Model {
+    let _window = sketch::window(app, app.new_window().resizable(false).view(view).build());
+
+    Model {
+        _window,
+        piece: PIECE
+            .take()
+            .expect("piece must be set before the app starts"),
+    }
+}
+
+fn update(_app: &App, _model: &mut Model, _update: Update) {}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+
+    if let Err(e) = model.piece.render(&draw, app.window_rect()) {
+        sketch::report(format_args!(
+            "Failed to render the {}: {}",
+            model.piece.sketch(),
+            e
+        ));
+    }
+
+    sketch::show(app, &draw, &frame);
+}
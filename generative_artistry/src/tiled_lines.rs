@@ -1,80 +1,401 @@
+use std::path::Path;
+
 use nannou::prelude::*;
+use nannou::rand::rngs::StdRng;
+use nannou::rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use sdx_art_lib::canvas::Canvas;
+use sdx_art_lib::error::{ensure_at_least, Error};
+use sdx_art_lib::field::{Field, FieldMapping, FieldSource};
+use sdx_art_lib::grid::{Cell, Grid};
+use sdx_art_lib::interactive::{self, bump_count, bump_value, Controls, Interactive, Tweak};
+use sdx_art_lib::metadata::Metadata;
+use sdx_art_lib::sketch::{self, Handoff};
+use sdx_art_lib::sketchy::Sketchy;
+use sdx_art_lib::stroke::StrokeStyle;
+use sdx_art_lib::watch::{self, Watch};
+
+use crate::piece::Piece;
+
+static DESCRIPTOR: Handoff<TiledLinesDescriptor> = Handoff::new();
+static FIELD: Handoff<Field> = Handoff::new();
+static WATCH: Handoff<Watch> = Handoff::new();
+
+/// How the line of each tile is oriented.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Orientation {
+    /// Coin flip per cell.
+    Random,
+    /// Sampled from a scalar field at the cell center.
+    Field(FieldSource, FieldMapping),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TiledLinesDescriptor {
+    width: u32,
+    height: u32,
+    grid: Grid,
+    orientation: Orientation,
+    stroke: StrokeStyle,
+    seed: u64,
+    sketchy: Option<Sketchy>,
+}
+
+impl TiledLinesDescriptor {
+    pub fn new(
+        width: u32,
+        height: u32,
+        grid: Grid,
+        orientation: Orientation,
+        stroke: StrokeStyle,
+        seed: u64,
+        sketchy: Option<Sketchy>,
+    ) -> TiledLinesDescriptor {
+        TiledLinesDescriptor {
+            width,
+            height,
+            grid,
+            orientation,
+            stroke,
+            seed,
+            sketchy,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("width", self.width as usize, 1)?;
+        ensure_at_least("height", self.height as usize, 1)?;
+        self.grid.validate()?;
+        if let Orientation::Field(source, mapping) = &self.orientation {
+            source.validate()?;
+            mapping.validate()?;
+        }
+        self.stroke.validate()?;
+        self.sketchy.as_ref().map_or(Ok(()), Sketchy::validate)
+    }
+
+    fn from_statics() -> TiledLinesDescriptor {
+        DESCRIPTOR
+            .take()
+            .expect("tiled lines descriptor must be set before the app starts")
+    }
+
+    /// The orientation field, if any. Image fields are read from disk again, so this can fail
+    /// even once the descriptor is validated.
+    fn build_field(&self) -> Result<Option<Field>, Error> {
+        match &self.orientation {
+            Orientation::Random => Ok(None),
+            Orientation::Field(source, _) => source.build().map(Some),
+        }
+    }
 
-static mut STEPS: i32 = 20;
+    /// Uses `seed` for the coin flips, the sketchy strokes and noise fields.
+    fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        if let Some(sketchy) = &mut self.sketchy {
+            sketchy.seed = seed;
+        }
+        if let Orientation::Field(
+            FieldSource::Perlin {
+                seed: field_seed, ..
+            }
+            | FieldSource::Simplex {
+                seed: field_seed, ..
+            }
+            | FieldSource::Value {
+                seed: field_seed, ..
+            },
+            _,
+        ) = &mut self.orientation
+        {
+            *field_seed = seed as u32;
+        }
+    }
+
+    /// Bumps the step size of the grid, the stroke weight or the threshold of the field.
+    fn bump(&mut self, parameter: usize, direction: i32) {
+        let delta = direction as f32;
+        match (parameter, &mut self.orientation) {
+            (0, _) => self.grid = self.bumped_grid(delta * 2.0),
+            (1, _) => {
+                self.stroke.weight = bump_value(self.stroke.weight, delta * 0.5, 0.5, f32::MAX)
+            }
+            (2, Orientation::Field(_, FieldMapping::Threshold(threshold))) => {
+                *threshold = bump_value(*threshold, delta * 0.05, 0.0, 1.0)
+            }
+            _ => (),
+        }
+    }
+
+    /// The grid with cells about `delta` larger, or at least one cell fewer or more per row.
+    fn bumped_grid(&self, delta: f32) -> Grid {
+        let (width, height) = (self.width as f32, self.height as f32);
+        let resize = |count: usize, length: f32| {
+            let step = (length / count as f32 + delta).max(2.0);
+            let resized = (length / step).ceil() as usize;
+            if resized != count {
+                resized.max(1)
+            } else if delta > 0.0 {
+                bump_count(count, -1, 1, usize::MAX)
+            } else {
+                count + 1
+            }
+        };
 
-pub fn present(size: u32, steps: i32) {
-    unsafe {
-        STEPS = steps;
+        match self.grid {
+            Grid::Rectangular { columns, rows } => Grid::Rectangular {
+                columns: resize(columns, width),
+                rows: resize(rows, height),
+            },
+            Grid::Hexagonal { radius } => Grid::Hexagonal {
+                radius: bump_value(radius, delta, 2.0, f32::MAX),
+            },
+            Grid::Triangular { side } => Grid::Triangular {
+                side: bump_value(side, delta, 2.0, f32::MAX),
+            },
+            Grid::Subdivided {
+                columns,
+                rows,
+                max_depth,
+                split_probability,
+            } => Grid::Subdivided {
+                columns: resize(columns, width),
+                rows: resize(rows, height),
+                max_depth,
+                split_probability,
+            },
+        }
     }
 
+    fn tweaks(&self) -> Vec<Tweak> {
+        let step = match self.grid {
+            Grid::Rectangular { columns, rows } | Grid::Subdivided { columns, rows, .. } => {
+                format!("{}x{} cells", columns, rows)
+            }
+            Grid::Hexagonal { radius } => format!("{} hexagon radius", radius),
+            Grid::Triangular { side } => format!("{} triangle side", side),
+        };
+
+        let mut tweaks = vec![
+            Tweak::new("step", step),
+            Tweak::new("stroke weight", self.stroke.weight),
+        ];
+        if let Orientation::Field(_, FieldMapping::Threshold(threshold)) = self.orientation {
+            tweaks.push(Tweak::new("threshold", format!("{:.2}", threshold)));
+        }
+        tweaks.push(Tweak::fixed("seed", self.seed));
+
+        tweaks
+    }
+}
+
+impl Piece for TiledLinesDescriptor {
+    fn sketch(&self) -> &'static str {
+        "tiled_lines"
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        TiledLinesDescriptor::validate(self)
+    }
+
+    fn render(&self, canvas: &dyn Canvas, rect: Rect) -> Result<(), Error> {
+        render(canvas, rect, self)
+    }
+}
+
+pub fn present(descriptor: TiledLinesDescriptor) -> Result<(), Error> {
+    run(descriptor, None)
+}
+
+/// Shows the descriptor read from `path`, tiling again in the same window whenever the file is
+/// saved. `descriptor` is written to the file first if it doesn't exist yet.
+pub fn watch(descriptor: TiledLinesDescriptor, path: &Path) -> Result<(), Error> {
+    run(
+        watch::load_or_create(path, descriptor)?,
+        Some(Watch::new(path)),
+    )
+}
+
+fn run(descriptor: TiledLinesDescriptor, watch: Option<Watch>) -> Result<(), Error> {
+    descriptor.validate()?;
+
+    let field = descriptor.build_field()?;
+
+    let (width, height) = (descriptor.width, descriptor.height);
+    let loop_mode = if watch.is_some() {
+        // Keeps updating, so changes of the file are noticed.
+        LoopMode::RefreshSync
+    } else {
+        LoopMode::Wait
+    };
+    DESCRIPTOR.put(descriptor);
+    FIELD.put(field);
+    WATCH.put(watch);
+
     nannou::app(model)
-        .loop_mode(LoopMode::loop_ntimes(1))
+        .loop_mode(loop_mode)
         .update(update)
-        .size(size, size)
+        .exit(interactive::exit)
+        .size(width, height)
         .run();
+
+    Ok(())
 }
 
 struct Model {
-    _window: window::Id,
+    _window: Option<window::Id>,
+    descriptor: TiledLinesDescriptor,
+    /// Built once per reseed or reload rather than on every redraw, as image fields are loaded
+    /// from disk.
+    field: Option<Field>,
+    controls: Controls,
+    watch: Option<Watch>,
 }
 
 fn model(app: &App) -> Model {
-    let _window = app.new_window()
-        .resizable(false)
-        .view(view).build().unwrap();
-    Model { _window }
+    let _window = sketch::window(
+        app,
+        app.new_window()
+            .title(interactive::title("tiled_lines"))
+            .resizable(false)
+            .view(view)
+            .key_pressed(interactive::key_pressed::<Model>)
+            .mouse_pressed(interactive::mouse_pressed::<Model>)
+            .build(),
+    );
+
+    let descriptor = TiledLinesDescriptor::from_statics();
+    Model {
+        _window,
+        field: FIELD.take(),
+        descriptor,
+        controls: Controls::default(),
+        watch: WATCH.take(),
+    }
 }
 
-fn update(_app: &App, _model: &mut Model, _update: Update) {}
+fn update(app: &App, model: &mut Model, _update: Update) {
+    if let Some(watch) = &mut model.watch {
+        if let Some(descriptor) = watch.reload_or_report(TiledLinesDescriptor::validate) {
+            // Fields failing to build are reported like invalid descriptors, keeping the last one.
+            match descriptor.build_field() {
+                Ok(field) => {
+                    if descriptor.size() != model.descriptor.size() {
+                        app.main_window().set_inner_size_points(
+                            descriptor.width as f32,
+                            descriptor.height as f32,
+                        );
+                    }
+                    model.field = field;
+                    model.descriptor = descriptor;
+                }
+                Err(e) => watch.report(e),
+            }
+        }
+    }
 
-fn view(app: &App, _model: &Model, frame: Frame) {
-    let draw = app.draw();
-    let window = app.window_rect();
+    interactive::update(app, model);
+}
 
-    draw.background().color(WHITE);
+impl Interactive for Model {
+    fn reseed(&mut self, _app: &App) {
+        self.descriptor.reseed(random());
+        match self.descriptor.build_field() {
+            Ok(field) => self.field = field,
+            Err(e) => sketch::report(format_args!("Failed to build the field: {}", e)),
+        }
+    }
 
-    let width = window.top_right().x * 2.0;
-    let height = window.top_right().y * 2.0;
+    fn bump(&mut self, parameter: usize, direction: i32) {
+        self.descriptor.bump(parameter, direction);
+    }
 
-    let steps = unsafe { STEPS };
+    fn tweaks(&self) -> Vec<Tweak> {
+        self.descriptor.tweaks()
+    }
 
-    let mut x = 0;
-    while x < width as i32 {
-        let mut y = 0;
-        while y < height as i32 {
-            draw_line(x as f32, y as f32, steps as f32, steps as f32, app, &draw);
-            y += steps;
-        }
-        x += steps;
+    fn metadata(&self, _app: &App) -> Metadata {
+        self.descriptor.metadata()
     }
 
-    draw.to_frame(app, &frame).unwrap();
+    fn controls(&self) -> &Controls {
+        &self.controls
+    }
 }
 
-fn draw_line(x: f32, y: f32, width: f32, height: f32, app: &App, draw: &Draw) {
-    let window = app.window_rect();
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    let rect = app.window_rect();
 
-    let (start, end) = if random::<bool>() {
-        let x_norm = window.top_left().x + x;
-        let y_norm = window.top_left().y - y;
+    draw_tiles(&draw, rect, &model.descriptor, model.field.as_ref());
+    interactive::draw_controls(app, &draw, rect, model);
 
-        let start = pt2(x_norm, y_norm);
-        let end = pt2(x_norm + width, y_norm - height);
+    sketch::show(app, &draw, &frame);
+}
 
-        (start, end)
-    } else {
-        let x_norm = window.bottom_left().x + x;
-        let y_norm = window.bottom_left().y + y;
+/// Draws one diagonal per grid cell, its direction picked at random or by the orientation field.
+pub fn render(
+    canvas: &dyn Canvas,
+    rect: Rect,
+    descriptor: &TiledLinesDescriptor,
+) -> Result<(), Error> {
+    draw_tiles(canvas, rect, descriptor, descriptor.build_field()?.as_ref());
+    Ok(())
+}
 
-        let start = pt2(x_norm, y_norm);
-        let end = pt2(x_norm + width, y_norm + height);
+fn draw_tiles(
+    canvas: &dyn Canvas,
+    rect: Rect,
+    descriptor: &TiledLinesDescriptor,
+    field: Option<&Field>,
+) {
+    let mut rng = StdRng::seed_from_u64(descriptor.seed);
+    let orientation = match &descriptor.orientation {
+        Orientation::Field(_, mapping) => field.map(|field| (field, *mapping)),
+        Orientation::Random => None,
+    };
+
+    Sketchy::wrap(descriptor.sketchy, canvas, |canvas| {
+        canvas.fill_background(Rgba::new(1.0, 1.0, 1.0, 1.0));
+
+        for cell in descriptor.grid.cells(rect, &mut rng) {
+            draw_line(
+                &cell,
+                orientation,
+                &descriptor.stroke,
+                &rect,
+                &mut rng,
+                canvas,
+            );
+        }
+    });
+}
 
-        (start, end)
+/// Draws one of the cell diagonals, picked at random or by the orientation field.
+fn draw_line<R: Rng>(
+    cell: &Cell,
+    orientation: Option<(&Field, FieldMapping)>,
+    stroke: &StrokeStyle,
+    rect: &Rect,
+    rng: &mut R,
+    canvas: &dyn Canvas,
+) {
+    let choices = cell.diagonal_count();
+    let i = match orientation {
+        None => rng.gen_range(0..choices),
+        Some((field, mapping)) => mapping.choose(field.sample_at(cell.center, *rect), choices, rng),
     };
 
-    draw.line()
-        .start(start)
-        .end(end)
-        .weight(3.0)
-        .color(BLACK);
+    let (start, end) = cell.diagonal(i);
+    canvas.stroke_path(&[start, end], stroke);
 }
@@ -0,0 +1,108 @@
+use nannou::prelude::*;
+use nannou::rand::rngs::StdRng;
+use nannou::rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use sdx_art_lib::canvas::Canvas;
+use sdx_art_lib::error::{
+    ensure_at_least, ensure_non_negative, ensure_positive, ensure_unit, Error,
+};
+use sdx_art_lib::lattice::{lattice_triangles, triangular_lattice};
+use sdx_art_lib::palette::Palette;
+use sdx_art_lib::shapes::polygon::Polygon;
+use sdx_art_lib::shapes::Drawable;
+use sdx_art_lib::stroke::StrokeStyle;
+
+use crate::piece::Piece;
+
+#[derive(Serialize, Deserialize)]
+pub struct TriangularMeshDescriptor {
+    size: u32,
+    spacing: f32,
+    /// Maximum vertex displacement as a fraction of the spacing.
+    jitter: f32,
+    palette: Palette,
+    /// Width of the triangle outlines, no outlines are drawn when zero.
+    line_width: f32,
+    seed: u64,
+}
+
+impl TriangularMeshDescriptor {
+    pub fn new(
+        size: u32,
+        spacing: f32,
+        jitter: f32,
+        palette: Palette,
+        line_width: f32,
+        seed: u64,
+    ) -> TriangularMeshDescriptor {
+        TriangularMeshDescriptor {
+            size,
+            spacing,
+            jitter,
+            palette,
+            line_width,
+            seed,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("size", self.size as usize, 1)?;
+        ensure_positive("spacing", self.spacing)?;
+        ensure_unit("jitter", self.jitter)?;
+        ensure_non_negative("line width", self.line_width)
+    }
+}
+
+impl Piece for TriangularMeshDescriptor {
+    fn sketch(&self) -> &'static str {
+        "triangular_mesh"
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.size, self.size)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        TriangularMeshDescriptor::validate(self)
+    }
+
+    fn render(&self, canvas: &dyn Canvas, rect: Rect) -> Result<(), Error> {
+        render(canvas, rect, self);
+        Ok(())
+    }
+}
+
+/// Fills the triangles of a jittered triangular lattice with colors picked from the palette.
+pub fn render(canvas: &dyn Canvas, rect: Rect, descriptor: &TriangularMeshDescriptor) {
+    let mut rng = StdRng::seed_from_u64(descriptor.seed);
+
+    canvas.fill_background(Rgba::new(1.0, 1.0, 1.0, 1.0));
+
+    let spacing = descriptor.spacing.max(1.0);
+    let rows = triangular_lattice(
+        rect,
+        spacing,
+        descriptor.jitter.clamp(0.0, 1.0) * spacing / 2.0,
+        &mut rng,
+    );
+
+    for triangle in lattice_triangles(&rows) {
+        let polygon = Polygon::new(triangle.to_vec()).with_fill(descriptor.palette.pick(&mut rng));
+
+        if descriptor.line_width > 0.0 {
+            polygon
+                .with_stroke(StrokeStyle::new(
+                    Rgba::new(0.0, 0.0, 0.0, 1.0),
+                    descriptor.line_width,
+                ))
+                .draw(canvas);
+        } else {
+            polygon.without_stroke().draw(canvas);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use nannou::prelude::*;
+use nannou::rand::rngs::StdRng;
+use nannou::rand::seq::SliceRandom;
+use nannou::rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use sdx_art_lib::canvas::Canvas;
+use sdx_art_lib::error::{
+    ensure_at_least, ensure_non_negative, ensure_positive, ensure_unit, Error,
+};
+use sdx_art_lib::glyph::LineGlyph;
+use sdx_art_lib::grid::rectangular_cells;
+use sdx_art_lib::stroke::{Cap, StrokeStyle};
+
+use crate::piece::Piece;
+
+#[derive(Serialize, Deserialize)]
+pub struct UnDeuxTroisDescriptor {
+    size: u32,
+    step: f32,
+    /// Vertical positions (0 at the top, 1 at the bottom) where another line is added per cell.
+    bands: Vec<f32>,
+    /// Rotations in degrees each glyph picks one from.
+    rotations: Vec<f32>,
+    /// Distance between the lines of a glyph as a fraction of the cell size.
+    spacing: f32,
+    line_width: f32,
+    seed: u64,
+}
+
+impl UnDeuxTroisDescriptor {
+    pub fn new(
+        size: u32,
+        step: f32,
+        bands: Vec<f32>,
+        rotations: Vec<f32>,
+        spacing: f32,
+        line_width: f32,
+        seed: u64,
+    ) -> UnDeuxTroisDescriptor {
+        UnDeuxTroisDescriptor {
+            size,
+            step,
+            bands,
+            rotations,
+            spacing,
+            line_width,
+            seed,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("size", self.size as usize, 1)?;
+        ensure_positive("step", self.step)?;
+        for band in &self.bands {
+            ensure_unit("band", *band)?;
+        }
+        if self.rotations.is_empty() {
+            return Err(Error::invalid("rotations", "must not be empty"));
+        }
+        ensure_non_negative("spacing", self.spacing)?;
+        ensure_non_negative("line width", self.line_width)
+    }
+
+    /// Number of lines drawn in a cell at the vertical position `t`.
+    fn line_count(&self, t: f32) -> usize {
+        1 + self.bands.iter().filter(|band| t >= **band).count()
+    }
+}
+
+impl Piece for UnDeuxTroisDescriptor {
+    fn sketch(&self) -> &'static str {
+        "un_deux_trois"
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.size, self.size)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        UnDeuxTroisDescriptor::validate(self)
+    }
+
+    fn render(&self, canvas: &dyn Canvas, rect: Rect) -> Result<(), Error> {
+        render(canvas, rect, self);
+        Ok(())
+    }
+}
+
+/// Draws a rotated glyph of parallel lines per cell, with more lines per glyph in each band
+/// further down.
+pub fn render(canvas: &dyn Canvas, rect: Rect, descriptor: &UnDeuxTroisDescriptor) {
+    let mut rng = StdRng::seed_from_u64(descriptor.seed);
+    let stroke =
+        StrokeStyle::new(Rgba::new(0.0, 0.0, 0.0, 1.0), descriptor.line_width).with_cap(Cap::Round);
+
+    canvas.fill_background(Rgba::new(1.0, 1.0, 1.0, 1.0));
+
+    let step = descriptor.step.max(1.0);
+    let margin = step / 2.0;
+    let inner = Rect::from_x_y_w_h(
+        rect.x(),
+        rect.y(),
+        rect.w() - margin * 2.0,
+        rect.h() - margin * 2.0,
+    );
+    let columns = (inner.w() / step).floor().max(1.0) as usize;
+    let rows = (inner.h() / step).floor().max(1.0) as usize;
+
+    for cell in rectangular_cells(inner, columns, rows) {
+        let t = (inner.top() - cell.y()) / inner.h();
+        let rotation = descriptor
+            .rotations
+            .choose(&mut rng)
+            .copied()
+            .unwrap_or(0.0);
+
+        LineGlyph::new(descriptor.line_count(t), cell.h() * 0.8)
+            .with_spacing(cell.w() * descriptor.spacing)
+            .with_rotation(deg_to_rad(rotation))
+            .draw(canvas, cell.xy(), &stroke);
+    }
+}
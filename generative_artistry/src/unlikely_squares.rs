@@ -0,0 +1,125 @@
+use nannou::prelude::*;
+use nannou::rand::rngs::StdRng;
+use nannou::rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use sdx_art_lib::canvas::Canvas;
+use sdx_art_lib::error::{ensure_at_least, ensure_non_negative, ensure_unit, Error};
+use sdx_art_lib::grid::rectangular_cells;
+use sdx_art_lib::palette::Palette;
+use sdx_art_lib::shapes::rectangle::Rectangle;
+use sdx_art_lib::shapes::Drawable;
+use sdx_art_lib::stroke::StrokeStyle;
+use sdx_art_lib::subdivision::quadtree;
+
+use crate::piece::Piece;
+
+#[derive(Serialize, Deserialize)]
+pub struct UnlikelySquaresDescriptor {
+    size: u32,
+    /// Number of squares per row and column the canvas starts out with.
+    cells: usize,
+    /// Maximum number of times a square is split into quarters.
+    depth: usize,
+    /// Chance of a starting square being split into quarters. Squares at depth `n` are split
+    /// with this chance to the power of `n + 1`, so splits get less and less likely.
+    split_probability: f32,
+    palette: Palette,
+    /// Chance of a square being filled with a palette color instead of white.
+    fill_chance: f32,
+    line_width: f32,
+    seed: u64,
+}
+
+impl UnlikelySquaresDescriptor {
+    pub fn new(
+        size: u32,
+        depth: usize,
+        split_probability: f32,
+        palette: Palette,
+        fill_chance: f32,
+        line_width: f32,
+        seed: u64,
+    ) -> UnlikelySquaresDescriptor {
+        UnlikelySquaresDescriptor {
+            size,
+            cells: 4,
+            depth,
+            split_probability,
+            palette,
+            fill_chance,
+            line_width,
+            seed,
+        }
+    }
+
+    /// Sets the number of squares per row and column, 4 by default.
+    pub fn with_cells(self, cells: usize) -> UnlikelySquaresDescriptor {
+        UnlikelySquaresDescriptor { cells, ..self }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("size", self.size as usize, 1)?;
+        ensure_at_least("cells", self.cells, 1)?;
+        ensure_unit("split probability", self.split_probability)?;
+        ensure_unit("fill chance", self.fill_chance)?;
+        ensure_non_negative("line width", self.line_width)
+    }
+}
+
+impl Piece for UnlikelySquaresDescriptor {
+    fn sketch(&self) -> &'static str {
+        "unlikely_squares"
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.size, self.size)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        UnlikelySquaresDescriptor::validate(self)
+    }
+
+    fn render(&self, canvas: &dyn Canvas, rect: Rect) -> Result<(), Error> {
+        render(canvas, rect, self);
+        Ok(())
+    }
+}
+
+/// Lays out a grid of squares and splits each into quarters, recursively and less likely the
+/// deeper it gets, then fills some of the resulting squares with palette colors.
+pub fn render(canvas: &dyn Canvas, rect: Rect, descriptor: &UnlikelySquaresDescriptor) {
+    let mut rng = StdRng::seed_from_u64(descriptor.seed);
+    let white = Rgba::new(1.0, 1.0, 1.0, 1.0);
+    let stroke = StrokeStyle::new(Rgba::new(0.0, 0.0, 0.0, 1.0), descriptor.line_width);
+
+    canvas.fill_background(white);
+
+    let split_probability = descriptor.split_probability.clamp(0.0, 1.0);
+    let fill_chance = descriptor.fill_chance.clamp(0.0, 1.0);
+
+    let mut squares = vec![];
+    for cell in rectangular_cells(rect, descriptor.cells, descriptor.cells) {
+        squares.extend(quadtree(cell, descriptor.depth, &mut |_, depth| {
+            rng.gen::<f32>() < split_probability.powi(depth as i32 + 1)
+        }));
+    }
+
+    for (square, _) in squares {
+        let fill = if rng.gen::<f32>() < fill_chance {
+            descriptor.palette.pick(&mut rng)
+        } else {
+            white
+        };
+
+        Rectangle::new(square.w(), square.h())
+            .with_x_y(square.x(), square.y())
+            .with_fill(fill)
+            .with_stroke(stroke.clone())
+            .draw(canvas);
+    }
+}
@@ -1,14 +1,98 @@
+use std::path::Path;
+
 use nannou::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use sdx_art_lib::error::{ensure_at_least, Error};
+use sdx_art_lib::interactive::{self, bump_count, Controls, Interactive, Tweak};
+use sdx_art_lib::metadata::Metadata;
+use sdx_art_lib::shapes::circular::Shape;
+use sdx_art_lib::shapes::Drawable;
+use sdx_art_lib::sketch::{self, Handoff};
+use sdx_art_lib::sketchy::Sketchy;
+use sdx_art_lib::watch::{self, Watch};
+
+static DESCRIPTOR: Handoff<FirstSketchDescriptor> = Handoff::new();
+static WATCH: Handoff<Watch> = Handoff::new();
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct FirstSketchDescriptor {
+    shapes: usize,
+    sketchy: Option<Sketchy>,
+}
+
+impl FirstSketchDescriptor {
+    pub fn new(shapes: usize, sketchy: Option<Sketchy>) -> FirstSketchDescriptor {
+        FirstSketchDescriptor { shapes, sketchy }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("shapes", self.shapes, 1)?;
+        self.sketchy.as_ref().map_or(Ok(()), Sketchy::validate)
+    }
+
+    fn from_statics() -> FirstSketchDescriptor {
+        DESCRIPTOR
+            .take()
+            .expect("first sketch descriptor must be set before the app starts")
+    }
+
+    fn tweaks(&self) -> Vec<Tweak> {
+        let mut tweaks = vec![Tweak::new("shapes", self.shapes)];
+        if let Some(sketchy) = self.sketchy {
+            tweaks.push(Tweak::fixed("seed", sketchy.seed));
+        }
+
+        tweaks
+    }
 
-use sdx_art_lib::shapes::circular::{draw_circular_shape, Shape};
+    /// Describes a frame of the sketch saved now. The shapes are placed at random rather than
+    /// from the seed, so unlike exports of pieces this can't be reproduced.
+    fn metadata(&self, rect: Rect) -> Metadata {
+        Metadata::new(
+            "first_sketch",
+            serde_json::to_value(self).expect("descriptors are always serializable"),
+            self.sketchy.map_or(0, |sketchy| sketchy.seed),
+            env!("CARGO_PKG_VERSION"),
+            (rect.w() as u32, rect.h() as u32),
+        )
+    }
+}
+
+pub fn present(descriptor: FirstSketchDescriptor) -> Result<(), Error> {
+    run(descriptor, None)
+}
+
+/// Shows the descriptor read from `path`, adding or removing shapes and keeping the others
+/// moving along whenever the file is saved. `descriptor` is written to the file first if it
+/// doesn't exist yet.
+pub fn watch(descriptor: FirstSketchDescriptor, path: &Path) -> Result<(), Error> {
+    run(
+        watch::load_or_create(path, descriptor)?,
+        Some(Watch::new(path)),
+    )
+}
+
+fn run(descriptor: FirstSketchDescriptor, watch: Option<Watch>) -> Result<(), Error> {
+    descriptor.validate()?;
+
+    DESCRIPTOR.put(descriptor);
+    WATCH.put(watch);
 
-pub fn present() {
-    nannou::app(model).update(update).run();
+    nannou::app(model)
+        .update(update)
+        .exit(interactive::exit)
+        .run();
+
+    Ok(())
 }
 
 struct Model {
-    _window: window::Id,
+    _window: Option<window::Id>,
     green_shapes: GreenShapes,
+    descriptor: FirstSketchDescriptor,
+    controls: Controls,
+    watch: Option<Watch>,
 }
 
 struct GreenShapes {
@@ -16,25 +100,26 @@ struct GreenShapes {
 }
 
 impl GreenShapes {
-    fn new(app: &App) -> GreenShapes {
+    fn new(app: &App, count: usize) -> GreenShapes {
         let _win = app.window_rect();
 
         let mut shapes = vec![];
 
-        for _i in 0..15 {
-            let (shape, radius) = Self::rng_green_shape();
-
-            let shape = shape.with_boundary(Rect::from_w_h(
-                radius + nannou::rand::random_range(25.0, 600.0),
-                radius + nannou::rand::random_range(25.0, 600.0),
-            ));
-
-            shapes.push(shape);
+        for _i in 0..count {
+            shapes.push(Self::bounded_green_shape());
         }
 
         GreenShapes { shapes }
     }
 
+    /// Drops the last shapes or adds new ones, leaving the others as they are.
+    fn resize(&mut self, count: usize) {
+        self.shapes.truncate(count);
+        while self.shapes.len() < count {
+            self.shapes.push(Self::bounded_green_shape());
+        }
+    }
+
     fn update(&mut self, app: &App) {
         self.shapes.iter_mut().for_each(|s| {
             s.update_pos(app);
@@ -45,6 +130,15 @@ impl GreenShapes {
         });
     }
 
+    fn bounded_green_shape() -> Shape {
+        let (shape, radius) = Self::rng_green_shape();
+
+        shape.with_boundary(Rect::from_w_h(
+            radius + nannou::rand::random_range(25.0, 600.0),
+            radius + nannou::rand::random_range(25.0, 600.0),
+        ))
+    }
+
     fn rng_green_shape() -> (Shape, f32) {
         let radius = nannou::rand::random_range(50.0, 150.0);
         let radius_offset = nannou::rand::random_range(0.0, 30.0);
@@ -68,16 +162,64 @@ impl GreenShapes {
 }
 
 fn model(app: &App) -> Model {
-    let _window = app.new_window().view(view).build().unwrap();
-
+    let _window = sketch::window(
+        app,
+        app.new_window()
+            .title(interactive::title("first_sketch"))
+            .view(view)
+            .key_pressed(interactive::key_pressed::<Model>)
+            .mouse_pressed(interactive::mouse_pressed::<Model>)
+            .build(),
+    );
+
+    let descriptor = FirstSketchDescriptor::from_statics();
     Model {
         _window,
-        green_shapes: GreenShapes::new(app),
+        green_shapes: GreenShapes::new(app, descriptor.shapes),
+        descriptor,
+        controls: Controls::default(),
+        watch: WATCH.take(),
     }
 }
 
 fn update(app: &App, model: &mut Model, _update: Update) {
+    if let Some(watch) = &mut model.watch {
+        if let Some(descriptor) = watch.reload_or_report(FirstSketchDescriptor::validate) {
+            model.green_shapes.resize(descriptor.shapes);
+            model.descriptor = descriptor;
+        }
+    }
+
     model.green_shapes.update(app);
+    interactive::update(app, model);
+}
+
+impl Interactive for Model {
+    fn reseed(&mut self, app: &App) {
+        if let Some(sketchy) = &mut self.descriptor.sketchy {
+            sketchy.seed = random();
+        }
+        self.green_shapes = GreenShapes::new(app, self.descriptor.shapes);
+    }
+
+    fn bump(&mut self, parameter: usize, direction: i32) {
+        if parameter == 0 {
+            self.descriptor.shapes = bump_count(self.descriptor.shapes, direction, 1, usize::MAX);
+            self.green_shapes.resize(self.descriptor.shapes);
+        }
+    }
+
+    fn tweaks(&self) -> Vec<Tweak> {
+        self.descriptor.tweaks()
+    }
+
+    fn metadata(&self, app: &App) -> Metadata {
+        self.descriptor.metadata(app.window_rect())
+    }
+
+    fn controls(&self) -> &Controls {
+        &self.controls
+    }
 }
 
 fn view(app: &App, model: &Model, frame: Frame) {
@@ -85,11 +227,14 @@ fn view(app: &App, model: &Model, frame: Frame) {
 
     frame.clear(DARKSLATEGREY);
 
-    model
-        .green_shapes
-        .shapes
-        .iter()
-        .for_each(|s| draw_circular_shape(&draw, s));
+    Sketchy::wrap(model.descriptor.sketchy, &draw, |canvas| {
+        model
+            .green_shapes
+            .shapes
+            .iter()
+            .for_each(|s| s.draw(canvas))
+    });
+    interactive::draw_controls(app, &draw, app.window_rect(), model);
 
-    draw.to_frame(app, &frame).unwrap();
+    sketch::show(app, &draw, &frame);
 }
@@ -1 +1,2 @@
 pub mod first_sketch;
+pub mod scene_sketch;
@@ -0,0 +1,111 @@
+use std::path::Path;
+
+use nannou::prelude::*;
+
+use sdx_art_lib::error::Error;
+use sdx_art_lib::scene::{Composition, Scene};
+use sdx_art_lib::sketch::{self, Handoff};
+use sdx_art_lib::watch::Watch;
+
+static SCENE: Handoff<Scene> = Handoff::new();
+static WATCH: Handoff<Watch> = Handoff::new();
+
+/// Shows `scene`, redrawing it continuously if any of its elements is animated.
+pub fn present(scene: Scene) -> Result<(), Error> {
+    run(scene, None)
+}
+
+/// Shows the scene at `path`, composing it again in the same window whenever the file is saved.
+/// Animations carry on from the time they're at.
+pub fn watch(path: &Path) -> Result<(), Error> {
+    run(Scene::load(path)?, Some(Watch::new(path)))
+}
+
+fn run(scene: Scene, watch: Option<Watch>) -> Result<(), Error> {
+    scene.validate()?;
+
+    let (width, height) = (scene.width, scene.height);
+    // Composed up front too, so scenes failing to compose are handed back to the caller.
+    scene.compose(Rect::from_w_h(width as f32, height as f32))?;
+    // Watched scenes keep updating too, so changes of the file are noticed.
+    let loop_mode = if scene.is_animated() || watch.is_some() {
+        LoopMode::RefreshSync
+    } else {
+        LoopMode::loop_ntimes(1)
+    };
+    SCENE.put(scene);
+    WATCH.put(watch);
+
+    nannou::app(model)
+        .loop_mode(loop_mode)
+        .update(update)
+        .size(width, height)
+        .run();
+
+    Ok(())
+}
+
+struct Model {
+    _window: Option<window::Id>,
+    /// `None` if the scene failed to compose, the app quitting then.
+    composition: Option<Composition>,
+    watch: Option<Watch>,
+}
+
+fn model(app: &App) -> Model {
+    let _window = sketch::window(app, app.new_window().resizable(false).view(view).build());
+
+    let scene = SCENE
+        .take()
+        .expect("scene must be set before the app starts");
+    let composition = scene
+        .compose(app.window_rect())
+        .map_err(|e| {
+            sketch::report(format_args!("Error: failed to compose the scene: {}", e));
+            app.quit();
+        })
+        .ok();
+
+    Model {
+        _window,
+        composition,
+        watch: WATCH.take(),
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    if let Some(watch) = &mut model.watch {
+        // Scenes failing to compose are reported like invalid ones, keeping the last composition.
+        let reloaded = watch.reload(Scene::validate).map(|scene| {
+            scene.and_then(|scene| {
+                let rect = Rect::from_w_h(scene.width as f32, scene.height as f32);
+                scene.compose(rect).map(|composition| (rect, composition))
+            })
+        });
+
+        match reloaded {
+            Some(Ok((rect, composition))) => {
+                if rect.wh() != app.window_rect().wh() {
+                    app.main_window().set_inner_size_points(rect.w(), rect.h());
+                }
+                model.composition = Some(composition);
+            }
+            Some(Err(e)) => watch.report(e),
+            None => (),
+        }
+    }
+
+    if let Some(composition) = &mut model.composition {
+        composition.update(app.time);
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+
+    if let Some(composition) = &model.composition {
+        composition.draw(&draw);
+    }
+
+    sketch::show(app, &draw, &frame);
+}
@@ -0,0 +1,320 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{App, AppSettings, Arg, ArgMatches, ArgSettings};
+
+/// Presets shipped with the CLI, in the same format as config files.
+const BUILTIN_PRESETS: &str = include_str!("../presets.toml");
+/// Config file read from the working directory if no other one is given.
+const DEFAULT_CONFIG: &str = "sdx_art.toml";
+const CONFIG_ENV: &str = "SDX_ART_CONFIG";
+const ENV_PREFIX: &str = "SDX_ART_";
+
+/// Parameters of one sketch, keyed by argument name or long flag.
+type Section = BTreeMap<String, toml::Value>;
+
+pub fn config_arg() -> Arg<'static> {
+    Arg::new("config")
+        .help(
+            "Config file with a [sketch] section of parameters per sketch, sdx_art.toml if present",
+        )
+        .long("config")
+        .value_name("FILE")
+        .takes_value(true)
+        .global(true)
+}
+
+pub fn preset_arg() -> Arg<'static> {
+    Arg::new("preset")
+        .help("Named set of parameters from the config file or the built in presets")
+        .long("preset")
+        .value_name("PRESET")
+        .takes_value(true)
+        .global(true)
+}
+
+/// Parses the command line, filling in the arguments of the chosen sketch that aren't given on
+/// it. In increasing priority, they are taken from the `[sketch]` section of the config file,
+/// the `[sketch.presets.NAME]` section picked with `--preset` and `SDX_ART_<SKETCH>_<ARG>`
+/// environment variables. Arguments given on the command line always win.
+///
+/// Like clap itself, this exits after printing help or the version, or if the arguments are
+/// invalid.
+pub fn get_matches(app: App<'static>) -> Result<ArgMatches> {
+    get_matches_from(app, env::args_os().collect()).map_err(|e| match e.downcast::<clap::Error>() {
+        Ok(e) => e.exit(),
+        Err(e) => e,
+    })
+}
+
+/// Like [`get_matches`], parsing `args` instead of the command line. Invalid arguments are
+/// returned as errors rather than exiting.
+pub fn get_matches_from(app: App<'static>, mut args: Vec<OsString>) -> Result<ArgMatches> {
+    // Only looks for the sketch and what's given on the command line, as arguments the sketch
+    // requires may still come from the config.
+    let matches = app
+        .clone()
+        .global_setting(AppSettings::IgnoreErrors)
+        .try_get_matches_from(&args)?;
+
+    let mut sketch_app = &app;
+    let mut sketch_matches = &matches;
+    while let Some((name, sub_matches)) = sketch_matches.subcommand() {
+        sketch_app = sketch_app
+            .find_subcommand(name)
+            .expect("matched subcommands are always defined");
+        sketch_matches = sub_matches;
+    }
+    let sketch = sketch_app.get_name();
+    if sketch == "sweep" {
+        // Sweeps pass the config and preset on to each render of their sketch instead.
+        return Ok(app.try_get_matches_from(args)?);
+    }
+
+    let config = load_config(sketch_matches.value_of("config"))?;
+    let builtin = parse_config(BUILTIN_PRESETS).expect("built in presets are valid TOML");
+
+    let mut sections = vec![section(&config, sketch)];
+    if let Some(preset) = sketch_matches.value_of("preset") {
+        let preset = self::preset(&config, sketch, preset)
+            .or_else(|| self::preset(&builtin, sketch, preset))
+            .ok_or_else(|| anyhow!("Unknown preset {} for {}.", preset, sketch))?;
+        sections.push(preset);
+    }
+    sections.push(env_section(sketch_app));
+
+    let mut parameters = BTreeMap::new();
+    for section in sections {
+        for (key, value) in section {
+            let arg = find_arg(sketch_app, &key)
+                .ok_or_else(|| anyhow!("Unknown parameter {} for {}.", key, sketch))?;
+            parameters.insert(arg.get_name(), (arg, value));
+        }
+    }
+
+    for (name, (arg, value)) in parameters {
+        if sketch_matches.occurrences_of(name) > 0 {
+            continue;
+        }
+
+        let long = arg
+            .get_long()
+            .ok_or_else(|| anyhow!("Parameter {} of {} can't be configured.", name, sketch))?;
+        if arg.is_set(ArgSettings::TakesValue) {
+            let value = arg_value(&value)
+                .ok_or_else(|| anyhow!("Unsupported value for parameter {}.", name))?;
+            args.push(format!("--{}={}", long, value).into());
+        } else if is_true(&value)
+            .ok_or_else(|| anyhow!("Parameter {} is a flag and takes true or false.", name))?
+        {
+            args.push(format!("--{}", long).into());
+        }
+    }
+
+    Ok(app.try_get_matches_from(args)?)
+}
+
+fn load_config(path: Option<&str>) -> Result<toml::value::Table> {
+    let path = match path.map(String::from).or_else(|| env::var(CONFIG_ENV).ok()) {
+        Some(path) => path,
+        None if Path::new(DEFAULT_CONFIG).exists() => String::from(DEFAULT_CONFIG),
+        None => return Ok(toml::value::Table::new()),
+    };
+
+    let text = fs::read_to_string(&path).with_context(|| format!("Failed to read {}.", path))?;
+    parse_config(&text).with_context(|| format!("Failed to parse {}.", path))
+}
+
+fn parse_config(text: &str) -> Result<toml::value::Table> {
+    match text.parse::<toml::Value>()? {
+        toml::Value::Table(table) => Ok(table),
+        _ => bail!("Expected a table of sketches."),
+    }
+}
+
+/// Parameters of the `[sketch]` section, without its presets.
+fn section(config: &toml::value::Table, sketch: &str) -> Section {
+    config
+        .get(sketch)
+        .and_then(toml::Value::as_table)
+        .map(|table| {
+            table
+                .iter()
+                .filter(|(key, _)| *key != "presets")
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn preset(config: &toml::value::Table, sketch: &str, preset: &str) -> Option<Section> {
+    config
+        .get(sketch)?
+        .get("presets")?
+        .get(preset)?
+        .as_table()
+        .map(|table| table.clone().into_iter().collect())
+}
+
+/// `SDX_ART_CIRCLE_PACKING_MAX_RADIUS=40` sets `max_radius` of `circle_packing`.
+///
+/// Variables not naming an argument of the sketch are ignored rather than rejected, as they may
+/// be meant for another sketch whose name starts the same, or for the CLI itself.
+fn env_section(sketch_app: &App<'static>) -> Section {
+    let prefix = format!("{}{}_", ENV_PREFIX, sketch_app.get_name().to_uppercase());
+
+    env::vars()
+        .filter_map(|(key, value)| {
+            let key = key.strip_prefix(&prefix)?.to_lowercase();
+            find_arg(sketch_app, &key)?;
+            Some((key, toml::Value::String(value)))
+        })
+        .collect()
+}
+
+/// Looks an argument up by its name or long flag, dashes and underscores being interchangeable.
+pub fn find_arg<'a>(app: &'a App<'static>, key: &str) -> Option<&'a Arg<'static>> {
+    let key = key.replace('-', "_");
+
+    app.get_arguments().find(|arg| {
+        arg.get_name() == key
+            || arg.get_long().map(|l| l.replace('-', "_")).as_deref() == Some(&key)
+    })
+}
+
+fn arg_value(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(s) => Some(s.clone()),
+        toml::Value::Integer(i) => Some(i.to_string()),
+        toml::Value::Float(f) => Some(f.to_string()),
+        toml::Value::Boolean(b) => Some(b.to_string()),
+        // Lists, e.g. of bands, are passed comma separated.
+        toml::Value::Array(values) => Some(
+            values
+                .iter()
+                .map(arg_value)
+                .collect::<Option<Vec<String>>>()?
+                .join(","),
+        ),
+        _ => None,
+    }
+}
+
+fn is_true(value: &toml::Value) -> Option<bool> {
+    match value {
+        toml::Value::Boolean(b) => Some(*b),
+        toml::Value::String(s) => match s.as_str() {
+            "true" | "1" => Some(true),
+            "false" | "0" | "" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sketch() -> App<'static> {
+        App::new("tiled_lines")
+            .arg(Arg::new("max_radius").long("max-radius").takes_value(true))
+            .arg(Arg::new("overlay").long("overlay"))
+            .arg(Arg::new("field").long("field").takes_value(true))
+            .arg(
+                Arg::new("image")
+                    .long("image")
+                    .takes_value(true)
+                    .required_if_eq("field", "image"),
+            )
+    }
+
+    #[test]
+    fn finds_args_by_name_or_long_flag() {
+        let app = sketch();
+        let name = |key| find_arg(&app, key).map(Arg::get_name);
+
+        assert_eq!(name("max_radius"), Some("max_radius"));
+        assert_eq!(name("max-radius"), Some("max_radius"));
+        assert_eq!(name("overlay"), Some("overlay"));
+        assert_eq!(name("radius"), None);
+    }
+
+    #[test]
+    fn formats_values_as_args() {
+        let value = |toml: &str| arg_value(&toml.parse::<toml::Value>().unwrap()["value"]);
+
+        assert_eq!(value("value = 'hex'").as_deref(), Some("hex"));
+        assert_eq!(value("value = 40").as_deref(), Some("40"));
+        assert_eq!(value("value = 2.5").as_deref(), Some("2.5"));
+        assert_eq!(value("value = true").as_deref(), Some("true"));
+        assert_eq!(value("value = [1, 2.5, 'x']").as_deref(), Some("1,2.5,x"));
+        assert_eq!(value("value = [[1], [2]]").as_deref(), Some("1,2"));
+        assert_eq!(value("value = { a = 1 }"), None);
+        assert_eq!(value("value = [{ a = 1 }]"), None);
+    }
+
+    #[test]
+    fn fills_in_required_args_from_the_config() {
+        let path = env::temp_dir().join(format!("sdx_art_config_{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "[tiled_lines]\nimage = 'photo.png'\nmax-radius = 3\n",
+        )
+        .unwrap();
+        let app = App::new("ga")
+            .arg(config_arg())
+            .arg(preset_arg())
+            .subcommand(sketch());
+        let args = [
+            "ga",
+            "--config",
+            path.to_str().unwrap(),
+            "tiled_lines",
+            "--field",
+            "image",
+            "--max-radius",
+            "5",
+        ];
+
+        let matches = get_matches_from(app, args.iter().map(OsString::from).collect());
+        fs::remove_file(&path).unwrap();
+
+        let matches = matches.unwrap();
+        let (_, matches) = matches.subcommand().unwrap();
+        assert_eq!(matches.value_of("image"), Some("photo.png"));
+        assert_eq!(matches.value_of("max_radius"), Some("5"));
+    }
+
+    #[test]
+    fn returns_invalid_args_instead_of_exiting() {
+        let app = App::new("ga")
+            .arg(config_arg())
+            .arg(preset_arg())
+            .subcommand(sketch());
+        let args = ["ga", "tiled_lines", "--unknown"];
+
+        let error = get_matches_from(app, args.iter().map(OsString::from).collect()).unwrap_err();
+        assert!(error.downcast_ref::<clap::Error>().is_some());
+    }
+
+    #[test]
+    fn ignores_variables_of_other_sketches() {
+        env::set_var("SDX_ART_CIRCLE_RADIUS", "3");
+        env::set_var("SDX_ART_CIRCLE_PACKING_MAX_RADIUS", "40");
+        let app = App::new("circle").arg(Arg::new("radius").long("radius").takes_value(true));
+
+        let section = env_section(&app);
+        env::remove_var("SDX_ART_CIRCLE_RADIUS");
+        env::remove_var("SDX_ART_CIRCLE_PACKING_MAX_RADIUS");
+
+        assert_eq!(
+            section.into_iter().collect::<Vec<_>>(),
+            [(String::from("radius"), toml::Value::from("3"))]
+        );
+    }
+}
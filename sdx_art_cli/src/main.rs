@@ -1,16 +1,293 @@
-use anyhow::{Context, Result};
-use clap::{App, AppSettings, Arg};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::{App, AppSettings, Arg, ArgMatches};
 
 use generative_artistry::circle_packing::CirclePackingDescriptor;
+use generative_artistry::contact_sheet::ContactSheet;
+use generative_artistry::cubic_disarray::{CubicDisarrayDescriptor, Falloff};
+use generative_artistry::hypnotic_squares::HypnoticSquaresDescriptor;
+use generative_artistry::joy_division::JoyDivisionDescriptor;
+use generative_artistry::mondrian::MondrianDescriptor;
+use generative_artistry::piece::{self, Piece};
+use generative_artistry::tiled_lines::{Orientation, TiledLinesDescriptor};
+use generative_artistry::triangular_mesh::TriangularMeshDescriptor;
+use generative_artistry::un_deux_trois::UnDeuxTroisDescriptor;
+use generative_artistry::unlikely_squares::UnlikelySquaresDescriptor;
+use nannou::geom::pt2;
+use sdx_art::first_sketch::FirstSketchDescriptor;
+use sdx_art_lib::color;
+use sdx_art_lib::contact_sheet::Layout;
+use sdx_art_lib::field::{FieldMapping, FieldSource};
+use sdx_art_lib::grid::Grid;
+use sdx_art_lib::metadata::Metadata;
+use sdx_art_lib::packing::Packing;
+use sdx_art_lib::palette::Palette;
+use sdx_art_lib::scene::Scene;
+use sdx_art_lib::sketchy::Sketchy;
+use sdx_art_lib::stroke::{Cap, StrokeStyle};
+
+mod config;
+mod sweep;
 
 fn main() -> Result<()> {
-    let matches = App::new("sdx_art_cli")
+    let matches = config::get_matches(cli())?;
+
+    if let Some(args) = matches.subcommand_matches("ga") {
+        if let Some(args) = args.subcommand_matches("circle_packing") {
+            let descriptor = circle_packing(args)?;
+            let descriptor = match args.value_of("save") {
+                Some(path) => {
+                    descriptor.validate()?;
+                    let packing = descriptor.pack();
+                    packing
+                        .save(path)
+                        .with_context(|| format!("Failed to save circles to {}.", path))?;
+                    descriptor.with_packing(packing)
+                }
+                None => descriptor,
+            };
+
+            show_watched(
+                args,
+                descriptor,
+                generative_artistry::circle_packing::present,
+                generative_artistry::circle_packing::watch,
+            )?;
+        }
+
+        if let Some(args) = args.subcommand_matches("tiled_lines") {
+            show_watched(
+                args,
+                tiled_lines(args)?,
+                generative_artistry::tiled_lines::present,
+                generative_artistry::tiled_lines::watch,
+            )?;
+        }
+
+        if let Some(args) = args.subcommand_matches("joy_division") {
+            show(args, joy_division(args)?, piece::present)?;
+        }
+
+        if let Some(args) = args.subcommand_matches("cubic_disarray") {
+            show(args, cubic_disarray(args)?, piece::present)?;
+        }
+
+        if let Some(args) = args.subcommand_matches("triangular_mesh") {
+            show(args, triangular_mesh(args)?, piece::present)?;
+        }
+
+        if let Some(args) = args.subcommand_matches("un_deux_trois") {
+            show(args, un_deux_trois(args)?, piece::present)?;
+        }
+
+        if let Some(args) = args.subcommand_matches("hypnotic_squares") {
+            show(args, hypnotic_squares(args)?, piece::present)?;
+        }
+
+        if let Some(args) = args.subcommand_matches("mondrian") {
+            show(args, mondrian(args)?, piece::present)?;
+        }
+
+        if let Some(args) = args.subcommand_matches("unlikely_squares") {
+            show(args, unlikely_squares(args)?, piece::present)?;
+        }
+    }
+
+    if let Some(args) = matches.subcommand_matches("run") {
+        let path = args.value_of("scene").unwrap();
+        let scene = Scene::load(path).with_context(|| format!("Failed to load scene {}.", path))?;
+
+        if args.is_present("watch") {
+            ensure_not_exporting(args, Path::new(path))?;
+            sdx_art::scene_sketch::watch(Path::new(path))?;
+        } else {
+            show(args, scene, sdx_art::scene_sketch::present)?;
+        }
+    }
+
+    if let Some(args) = matches.subcommand_matches("reproduce") {
+        let path = Path::new(args.value_of("export").unwrap());
+        let metadata = Metadata::read(path)
+            .with_context(|| format!("Failed to read metadata of {}.", path.display()))?;
+        if metadata.version != piece::VERSION {
+            eprintln!(
+                "{} was rendered with version {}, this is {}. It may look different.",
+                path.display(),
+                metadata.version,
+                piece::VERSION
+            );
+        }
+
+        let piece = piece::from_metadata(&metadata)?;
+        match args.value_of("output") {
+            Some(output) => piece::export(piece, Path::new(output))?,
+            None => piece::present_boxed(piece)?,
+        }
+    }
+
+    if let Some(args) = matches.subcommand_matches("sheet") {
+        let pieces = args
+            .values_of("exports")
+            .unwrap()
+            .map(|path| {
+                Metadata::read(Path::new(path))
+                    .with_context(|| format!("Failed to read metadata of {}.", path))
+            })
+            .collect::<Result<Vec<Metadata>>>()?;
+        let cell = args
+            .value_of("cell")
+            .unwrap()
+            .parse::<f32>()
+            .context("Failed to parse cell arg into a f32 type.")?;
+
+        let layout = Layout::new(pieces.len(), cell);
+        let layout = match args.value_of("columns") {
+            Some(columns) => layout.with_columns(
+                columns
+                    .parse::<usize>()
+                    .context("Failed to parse columns arg into a usize type.")?,
+            ),
+            None => layout,
+        };
+        let sheet = if args.is_present("captions") {
+            ContactSheet::new(pieces, layout.with_caption_size(cell / 16.0))
+                .with_parameter_captions()
+        } else {
+            ContactSheet::new(pieces, layout)
+        };
+
+        show(args, sheet, piece::present)?;
+    }
+
+    if let Some(args) = matches.subcommand_matches("sweep") {
+        sweep::run(cli(), args, ga_piece)?;
+    }
+
+    if let Some(args) = matches.subcommand_matches("sdx") {
+        let sketchy = if args.is_present("sketchy") {
+            Some(Sketchy::new(parse_seed(args)?))
+        } else {
+            None
+        };
+
+        let shapes = args
+            .value_of("shapes")
+            .unwrap()
+            .parse::<usize>()
+            .context("Failed to parse shapes arg into a usize type.")?;
+
+        let descriptor = FirstSketchDescriptor::new(shapes, sketchy);
+        match args.value_of("watch") {
+            Some(path) => sdx_art::first_sketch::watch(descriptor, Path::new(path))?,
+            None => sdx_art::first_sketch::present(descriptor)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn cli() -> App<'static> {
+    App::new("sdx_art_cli")
         .setting(AppSettings::SubcommandRequiredElseHelp)
-        .subcommand(App::new("sdx").about("My own stuff"))
+        .arg(config::config_arg())
+        .arg(config::preset_arg())
+        .subcommand(
+            App::new("sdx")
+                .about("My own stuff")
+                .arg(
+                    Arg::new("shapes")
+                        .help("Number of shapes, adjustable with the arrow keys while shown")
+                        .long("shapes")
+                        .value_name("COUNT")
+                        .takes_value(true)
+                        .default_value("15"),
+                )
+                .arg(watch_arg())
+                .arg(
+                    Arg::new("sketchy")
+                        .help("Render with wobbly, overshooting pencil lines")
+                        .long("sketchy")
+                        .takes_value(false),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .help("Random seed of the pencil lines, a random one is used if omitted")
+                        .long("seed")
+                        .value_name("SEED")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            App::new("run")
+                .about("Render a scene described in a TOML, JSON or RON file")
+                .arg(
+                    Arg::new("scene")
+                        .help("Path of the scene file, its extension selecting the format")
+                        .value_name("SCENE")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("watch")
+                        .help("Compose the scene again whenever its file is saved")
+                        .long("watch")
+                        .takes_value(false),
+                )
+                .arg(output_arg()),
+        )
+        .subcommand(
+            App::new("reproduce")
+                .about("Render a piece again from the metadata of an export")
+                .arg(
+                    Arg::new("export")
+                        .help("Exported .png or .svg, or its .json sidecar")
+                        .value_name("EXPORT")
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(output_arg()),
+        )
+        .subcommand(
+            App::new("sheet")
+                .about("Lay exported pieces out in a grid on one canvas")
+                .arg(
+                    Arg::new("exports")
+                        .help("Exported .png or .svg files, or their .json sidecars")
+                        .value_name("EXPORTS")
+                        .required(true)
+                        .takes_value(true)
+                        .multiple_values(true),
+                )
+                .arg(
+                    Arg::new("columns")
+                        .help("Number of columns, about as many as rows if omitted")
+                        .long("columns")
+                        .value_name("COLUMNS")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("cell")
+                        .help("Size of the square cells the pieces are scaled to fit")
+                        .long("cell")
+                        .value_name("CELL")
+                        .takes_value(true)
+                        .default_value("256"),
+                )
+                .arg(
+                    Arg::new("captions")
+                        .help("Caption the pieces with the parameters they don't share with the others of their sketch")
+                        .long("captions")
+                        .takes_value(false),
+                )
+                .arg(output_arg()),
+        )
+        .subcommand(sweep::app())
         .subcommand(
             App::new("ga")
                 .setting(AppSettings::SubcommandRequiredElseHelp)
                 .about("Generative Artistry Tutorial implementations")
+                .arg(output_arg().global(true))
                 .subcommand(
                     App::new("tiled_lines")
                         .about("Tiled lines tutorial implementation of generative artistry")
@@ -20,17 +297,149 @@ fn main() -> Result<()> {
                             .value_name("SIZE")
                             .takes_value(true)
                             .default_value("320"))
+                        .arg(Arg::new("width")
+                            .help("Width of canvas, defaults to size")
+                            .long("width")
+                            .value_name("WIDTH")
+                            .takes_value(true))
+                        .arg(Arg::new("height")
+                            .help("Height of canvas, defaults to size")
+                            .long("height")
+                            .value_name("HEIGHT")
+                            .takes_value(true))
                         .arg(Arg::new("steps")
                             .help("Tiling steps. Less results in more iterations of tiles.")
                             .short('s')
                             .long("steps")
                             .value_name("STEPS")
                             .takes_value(true)
-                            .default_value("20")),
+                            .default_value("20"))
+                        .arg(Arg::new("columns")
+                            .help("Number of columns, derived from steps if omitted")
+                            .long("columns")
+                            .value_name("COLUMNS")
+                            .takes_value(true))
+                        .arg(Arg::new("rows")
+                            .help("Number of rows, derived from steps if omitted")
+                            .long("rows")
+                            .value_name("ROWS")
+                            .takes_value(true))
+                        .arg(Arg::new("grid")
+                            .help("Cell layout. hex and tri use steps as cell size, quad randomly subdivides the rect cells.")
+                            .short('g')
+                            .long("grid")
+                            .value_name("GRID")
+                            .takes_value(true)
+                            .possible_values(["rect", "hex", "tri", "quad"])
+                            .default_value("rect"))
+                        .arg(Arg::new("depth")
+                            .help("Maximum subdivision depth of quad grids")
+                            .long("depth")
+                            .value_name("DEPTH")
+                            .takes_value(true)
+                            .default_value("3"))
+                        .arg(Arg::new("split")
+                            .help("Probability of a quad grid cell being split")
+                            .long("split")
+                            .value_name("SPLIT_PROBABILITY")
+                            .takes_value(true)
+                            .default_value("0.5"))
+                        .arg(Arg::new("field")
+                            .help("Scalar field choosing the tile orientation instead of a coin flip")
+                            .short('f')
+                            .long("field")
+                            .value_name("FIELD")
+                            .takes_value(true)
+                            .possible_values(["random", "perlin", "simplex", "value", "radial", "image", "expression"])
+                            .default_value("random"))
+                        .arg(Arg::new("scale")
+                            .help("Noise frequency across the canvas")
+                            .long("scale")
+                            .value_name("SCALE")
+                            .takes_value(true)
+                            .default_value("4.0"))
+                        .arg(Arg::new("field_seed")
+                            .help("Seed of the noise field")
+                            .long("field-seed")
+                            .value_name("FIELD_SEED")
+                            .takes_value(true)
+                            .default_value("0"))
+                        .arg(Arg::new("image")
+                            .help("Image whose luminance is used by the image field")
+                            .long("image")
+                            .value_name("IMAGE")
+                            .takes_value(true)
+                            .required_if_eq("field", "image"))
+                        .arg(Arg::new("expression")
+                            .help("Expression of the normalized position x, y and the distance r from the center, used by the expression field")
+                            .long("expression")
+                            .value_name("EXPRESSION")
+                            .takes_value(true)
+                            .required_if_eq("field", "expression"))
+                        .arg(Arg::new("mapping")
+                            .help("Whether field values are thresholded or used as probability")
+                            .long("mapping")
+                            .value_name("MAPPING")
+                            .takes_value(true)
+                            .possible_values(["threshold", "probability"])
+                            .default_value("threshold"))
+                        .arg(Arg::new("threshold")
+                            .help("Threshold for the threshold mapping")
+                            .long("threshold")
+                            .value_name("THRESHOLD")
+                            .takes_value(true)
+                            .default_value("0.5"))
+                        .arg(Arg::new("weight")
+                            .help("Line weight")
+                            .short('w')
+                            .long("weight")
+                            .value_name("WEIGHT")
+                            .takes_value(true)
+                            .default_value("3.0"))
+                        .arg(Arg::new("color")
+                            .help("Line color as hex value")
+                            .short('c')
+                            .long("color")
+                            .value_name("COLOR")
+                            .takes_value(true)
+                            .default_value("#000000"))
+                        .arg(Arg::new("cap")
+                            .help("Line cap")
+                            .long("cap")
+                            .value_name("CAP")
+                            .takes_value(true)
+                            .possible_values(["butt", "round", "square"])
+                            .default_value("butt"))
+                        .arg(Arg::new("sketchy")
+                            .help("Render with wobbly, overshooting pencil lines")
+                            .long("sketchy")
+                            .takes_value(false))
+                        .arg(Arg::new("seed")
+                            .help("Random seed, a random one is used if omitted")
+                            .long("seed")
+                            .value_name("SEED")
+                            .takes_value(true))
+                        .arg(watch_arg()),
                 )
                 .subcommand(
                     App::new("circle_packing")
                         .about("Circle packing tutorial implementation of generative artistry")
+                        .arg(
+                            Arg::new("width")
+                                .help("Width of canvas")
+                                .long("width")
+                                .value_name("WIDTH")
+                                .takes_value(true)
+                                .default_value("1024"),
+                        )
+                        .arg(
+                            Arg::new("height")
+                                .help("Height of canvas")
+                                .long("height")
+                                .value_name("HEIGHT")
+                                .takes_value(true)
+                                .default_value("768"),
+                        )
                         .arg(
                             Arg::new("line_width")
                                 .help("Line width of circles")
@@ -75,61 +484,969 @@ fn main() -> Result<()> {
                                 .value_name("CREATE_CIRCLE_ATTEMPTS")
                                 .takes_value(true)
                                 .default_value("500"),
+                        )
+                        .arg(
+                            Arg::new("sketchy")
+                                .help("Render with wobbly, overshooting pencil lines")
+                                .long("sketchy")
+                                .takes_value(false),
+                        )
+                        .arg(
+                            Arg::new("seed")
+                                .help("Random seed, a random one is used if omitted")
+                                .long("seed")
+                                .value_name("SEED")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::new("load")
+                                .help("Draw the circles saved in a .toml, .json or .ron file instead of packing new ones")
+                                .long("load")
+                                .value_name("FILE")
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::new("save")
+                                .help("Save the packed circles to a .toml, .json or .ron file")
+                                .long("save")
+                                .value_name("FILE")
+                                .takes_value(true),
+                        )
+                        .arg(watch_arg()),
+                )
+                .subcommand(
+                    App::new("joy_division")
+                        .about("Joy division tutorial implementation of generative artistry")
+                        .arg(
+                            Arg::new("size")
+                                .help("Size of canvas")
+                                .long("size")
+                                .value_name("SIZE")
+                                .takes_value(true)
+                                .default_value("320"),
+                        )
+                        .arg(
+                            Arg::new("lines")
+                                .help("Number of ridge lines")
+                                .short('l')
+                                .long("lines")
+                                .value_name("LINES")
+                                .takes_value(true)
+                                .default_value("24"),
+                        )
+                        .arg(
+                            Arg::new("points")
+                                .help("Number of points per line")
+                                .short('p')
+                                .long("points")
+                                .value_name("POINTS")
+                                .takes_value(true)
+                                .default_value("40"),
+                        )
+                        .arg(
+                            Arg::new("amplitude")
+                                .help("Maximum height of the peaks")
+                                .short('a')
+                                .long("amplitude")
+                                .value_name("AMPLITUDE")
+                                .takes_value(true)
+                                .default_value("60.0"),
+                        )
+                        .arg(
+                            Arg::new("distortion")
+                                .help("Exponent of the center weighting. Higher values narrow the peaks towards the center.")
+                                .short('d')
+                                .long("distortion")
+                                .value_name("DISTORTION")
+                                .takes_value(true)
+                                .default_value("1.0"),
+                        )
+                        .arg(
+                            Arg::new("line_width")
+                                .help("Line width of the ridges")
+                                .short('w')
+                                .long("line")
+                                .value_name("LINE_WIDTH")
+                                .takes_value(true)
+                                .default_value("2.0"),
+                        )
+                        .arg(
+                            Arg::new("seed")
+                                .help("Random seed, a random one is used if omitted")
+                                .long("seed")
+                                .value_name("SEED")
+                                .takes_value(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("cubic_disarray")
+                        .about("Cubic disarray tutorial implementation of generative artistry")
+                        .arg(
+                            Arg::new("size")
+                                .help("Size of canvas")
+                                .long("size")
+                                .value_name("SIZE")
+                                .takes_value(true)
+                                .default_value("320"),
+                        )
+                        .arg(
+                            Arg::new("square_size")
+                                .help("Size of the squares")
+                                .short('q')
+                                .long("square")
+                                .value_name("SQUARE_SIZE")
+                                .takes_value(true)
+                                .default_value("30.0"),
+                        )
+                        .arg(
+                            Arg::new("displacement")
+                                .help("Maximum displacement of the bottom row squares")
+                                .short('d')
+                                .long("displacement")
+                                .value_name("DISPLACEMENT")
+                                .takes_value(true)
+                                .default_value("15.0"),
+                        )
+                        .arg(
+                            Arg::new("rotation")
+                                .help("Maximum rotation of the bottom row squares in degrees")
+                                .short('r')
+                                .long("rotation")
+                                .value_name("ROTATION")
+                                .takes_value(true)
+                                .default_value("20.0"),
+                        )
+                        .arg(
+                            Arg::new("falloff")
+                                .help("How fast the disarray grows from top to bottom")
+                                .short('f')
+                                .long("falloff")
+                                .value_name("FALLOFF")
+                                .takes_value(true)
+                                .possible_values(["linear", "quadratic", "exponential"])
+                                .default_value("linear"),
+                        )
+                        .arg(
+                            Arg::new("line_width")
+                                .help("Line width of the squares")
+                                .short('l')
+                                .long("line")
+                                .value_name("LINE_WIDTH")
+                                .takes_value(true)
+                                .default_value("2.0"),
+                        )
+                        .arg(
+                            Arg::new("seed")
+                                .help("Random seed, a random one is used if omitted")
+                                .long("seed")
+                                .value_name("SEED")
+                                .takes_value(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("triangular_mesh")
+                        .about("Triangular mesh tutorial implementation of generative artistry")
+                        .arg(
+                            Arg::new("size")
+                                .help("Size of canvas")
+                                .long("size")
+                                .value_name("SIZE")
+                                .takes_value(true)
+                                .default_value("320"),
+                        )
+                        .arg(
+                            Arg::new("spacing")
+                                .help("Distance between the lattice points")
+                                .short('s')
+                                .long("spacing")
+                                .value_name("SPACING")
+                                .takes_value(true)
+                                .default_value("40.0"),
+                        )
+                        .arg(
+                            Arg::new("jitter")
+                                .help("Maximum displacement of the lattice points as a fraction of the spacing")
+                                .short('j')
+                                .long("jitter")
+                                .value_name("JITTER")
+                                .takes_value(true)
+                                .default_value("0.4"),
+                        )
+                        .arg(
+                            Arg::new("palette")
+                                .help("Colors the triangles are filled with")
+                                .short('p')
+                                .long("palette")
+                                .value_name("PALETTE")
+                                .takes_value(true)
+                                .possible_values(["grayscale", "primary", "pastel", "greens"])
+                                .default_value("grayscale"),
+                        )
+                        .arg(
+                            Arg::new("line_width")
+                                .help("Line width of the triangle outlines, none are drawn if 0")
+                                .short('l')
+                                .long("line")
+                                .value_name("LINE_WIDTH")
+                                .takes_value(true)
+                                .default_value("1.0"),
+                        )
+                        .arg(
+                            Arg::new("seed")
+                                .help("Random seed, a random one is used if omitted")
+                                .long("seed")
+                                .value_name("SEED")
+                                .takes_value(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("un_deux_trois")
+                        .about("Un deux trois tutorial implementation of generative artistry")
+                        .arg(
+                            Arg::new("size")
+                                .help("Size of canvas")
+                                .long("size")
+                                .value_name("SIZE")
+                                .takes_value(true)
+                                .default_value("320"),
+                        )
+                        .arg(
+                            Arg::new("step")
+                                .help("Size of the cells")
+                                .short('s')
+                                .long("step")
+                                .value_name("STEP")
+                                .takes_value(true)
+                                .default_value("20.0"),
+                        )
+                        .arg(
+                            Arg::new("bands")
+                                .help("Comma separated vertical positions from 0 (top) to 1 (bottom) where another line is added")
+                                .short('b')
+                                .long("bands")
+                                .value_name("BANDS")
+                                .takes_value(true)
+                                .default_value("0.333,0.667"),
+                        )
+                        .arg(
+                            Arg::new("rotations")
+                                .help("Comma separated rotations in degrees the lines pick from")
+                                .short('r')
+                                .long("rotations")
+                                .value_name("ROTATIONS")
+                                .takes_value(true)
+                                .default_value("0,45,90,135"),
+                        )
+                        .arg(
+                            Arg::new("spacing")
+                                .help("Distance between the lines of a cell as a fraction of the cell size")
+                                .short('p')
+                                .long("spacing")
+                                .value_name("SPACING")
+                                .takes_value(true)
+                                .default_value("0.3"),
+                        )
+                        .arg(
+                            Arg::new("line_width")
+                                .help("Line width")
+                                .short('l')
+                                .long("line")
+                                .value_name("LINE_WIDTH")
+                                .takes_value(true)
+                                .default_value("2.0"),
+                        )
+                        .arg(
+                            Arg::new("seed")
+                                .help("Random seed, a random one is used if omitted")
+                                .long("seed")
+                                .value_name("SEED")
+                                .takes_value(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("hypnotic_squares")
+                        .about("Hypnotic squares tutorial implementation of generative artistry")
+                        .arg(
+                            Arg::new("size")
+                                .help("Size of canvas")
+                                .long("size")
+                                .value_name("SIZE")
+                                .takes_value(true)
+                                .default_value("320"),
+                        )
+                        .arg(
+                            Arg::new("tiles")
+                                .help("Number of tiles per row and column")
+                                .short('t')
+                                .long("tiles")
+                                .value_name("TILES")
+                                .takes_value(true)
+                                .default_value("7"),
+                        )
+                        .arg(
+                            Arg::new("depth")
+                                .help("Number of squares nested into each tile")
+                                .short('d')
+                                .long("depth")
+                                .value_name("DEPTH")
+                                .takes_value(true)
+                                .default_value("5"),
+                        )
+                        .arg(
+                            Arg::new("min_size")
+                                .help("Size of the innermost squares")
+                                .short('m')
+                                .long("min")
+                                .value_name("MIN_SIZE")
+                                .takes_value(true)
+                                .default_value("3.0"),
+                        )
+                        .arg(
+                            Arg::new("offset")
+                                .help("How far the nested squares drift towards the tile edge, from 0 to 1")
+                                .short('o')
+                                .long("offset")
+                                .value_name("OFFSET")
+                                .takes_value(true)
+                                .default_value("0.8"),
+                        )
+                        .arg(
+                            Arg::new("line_width")
+                                .help("Line width of the squares")
+                                .short('l')
+                                .long("line")
+                                .value_name("LINE_WIDTH")
+                                .takes_value(true)
+                                .default_value("2.0"),
+                        )
+                        .arg(
+                            Arg::new("seed")
+                                .help("Random seed, a random one is used if omitted")
+                                .long("seed")
+                                .value_name("SEED")
+                                .takes_value(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("mondrian")
+                        .about("Piet Mondrian tutorial implementation of generative artistry")
+                        .arg(
+                            Arg::new("size")
+                                .help("Size of canvas")
+                                .long("size")
+                                .value_name("SIZE")
+                                .takes_value(true)
+                                .default_value("320"),
+                        )
+                        .arg(
+                            Arg::new("step")
+                                .help("Distance between the lines rectangles may be split along")
+                                .short('s')
+                                .long("step")
+                                .value_name("STEP")
+                                .takes_value(true)
+                                .default_value("40.0"),
+                        )
+                        .arg(
+                            Arg::new("split_chance")
+                                .help("Chance of a rectangle being split by a line crossing it")
+                                .short('p')
+                                .long("split")
+                                .value_name("SPLIT_CHANCE")
+                                .takes_value(true)
+                                .default_value("0.5"),
+                        )
+                        .arg(
+                            Arg::new("colored")
+                                .help("Number of rectangles filled with a primary color")
+                                .short('c')
+                                .long("colored")
+                                .value_name("COLORED")
+                                .takes_value(true)
+                                .default_value("3"),
+                        )
+                        .arg(
+                            Arg::new("line_width")
+                                .help("Line width of the borders")
+                                .short('l')
+                                .long("line")
+                                .value_name("LINE_WIDTH")
+                                .takes_value(true)
+                                .default_value("8.0"),
+                        )
+                        .arg(
+                            Arg::new("seed")
+                                .help("Random seed, a random one is used if omitted")
+                                .long("seed")
+                                .value_name("SEED")
+                                .takes_value(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("unlikely_squares")
+                        .about("Unlikely squares tutorial implementation of generative artistry")
+                        .arg(
+                            Arg::new("size")
+                                .help("Size of canvas")
+                                .long("size")
+                                .value_name("SIZE")
+                                .takes_value(true)
+                                .default_value("320"),
+                        )
+                        .arg(
+                            Arg::new("cells")
+                                .help("Number of squares per row and column before splitting")
+                                .short('c')
+                                .long("cells")
+                                .value_name("CELLS")
+                                .takes_value(true)
+                                .default_value("4"),
+                        )
+                        .arg(
+                            Arg::new("depth")
+                                .help("Maximum number of times a square is split into quarters")
+                                .short('d')
+                                .long("depth")
+                                .value_name("DEPTH")
+                                .takes_value(true)
+                                .default_value("5"),
+                        )
+                        .arg(
+                            Arg::new("split_probability")
+                                .help("Chance of a square being split into quarters, decaying with depth")
+                                .short('p')
+                                .long("split-probability")
+                                .value_name("SPLIT_PROBABILITY")
+                                .takes_value(true)
+                                .default_value("0.9"),
+                        )
+                        .arg(
+                            Arg::new("palette")
+                                .help("Colors the squares are filled with")
+                                .long("palette")
+                                .value_name("PALETTE")
+                                .takes_value(true)
+                                .possible_values(["grayscale", "primary", "pastel", "greens"])
+                                .default_value("primary"),
+                        )
+                        .arg(
+                            Arg::new("fill_chance")
+                                .help("Chance of a square being filled with a palette color")
+                                .short('f')
+                                .long("fill")
+                                .value_name("FILL_CHANCE")
+                                .takes_value(true)
+                                .default_value("0.3"),
+                        )
+                        .arg(
+                            Arg::new("line_width")
+                                .help("Line width of the squares")
+                                .short('l')
+                                .long("line")
+                                .value_name("LINE_WIDTH")
+                                .takes_value(true)
+                                .default_value("2.0"),
+                        )
+                        .arg(
+                            Arg::new("seed")
+                                .help("Random seed, a random one is used if omitted")
+                                .long("seed")
+                                .value_name("SEED")
+                                .takes_value(true),
                         ),
                 ),
         )
-        .get_matches();
+}
 
-    if let Some(args) = matches.subcommand_matches("ga") {
-        if let Some(args) = args.subcommand_matches("circle_packing") {
-            let line_width = args
-                .value_of("line_width")
-                .unwrap()
-                .parse::<f32>()
-                .context("Failed to parse line_width arg into a f32 type.")?;
-            let min_radius = args
-                .value_of("min_radius")
-                .unwrap()
-                .parse::<usize>()
-                .context("Failed to parse min_radius arg into a usize type.")?;
-            let max_radius = args
-                .value_of("max_radius")
-                .unwrap()
-                .parse::<usize>()
-                .context("Failed to parse max_radius arg into a usize type.")?;
-            let total_circles = args
-                .value_of("total_circles")
-                .unwrap()
-                .parse::<usize>()
-                .context("Failed to parse total_circles arg into a usize type.")?;
-            let create_circle_attempts = args
-                .value_of("create_circle_attempts")
-                .unwrap()
-                .parse::<usize>()
-                .context("Failed to parse create_circle_attempts arg into a usize type.")?;
-
-            let descriptor = CirclePackingDescriptor::new(
-                line_width,
-                min_radius,
-                max_radius,
-                total_circles,
-                create_circle_attempts,
-            );
+fn output_arg() -> Arg<'static> {
+    Arg::new("output")
+        .help("Export to a .png or .svg file instead of showing a window, along with a .json metadata sidecar")
+        .long("output")
+        .value_name("FILE")
+        .takes_value(true)
+}
 
-            generative_artistry::circle_packing::present(descriptor);
-        }
+fn watch_arg() -> Arg<'static> {
+    Arg::new("watch")
+        .help("Show the parameters in FILE instead, reloading them whenever it's saved. The given parameters are written to FILE first if it doesn't exist")
+        .long("watch")
+        .value_name("FILE")
+        .takes_value(true)
+}
+
+/// Exports the piece if an output file is given, otherwise shows it with `present`.
+fn show<P: Piece + 'static>(
+    args: &ArgMatches,
+    piece: P,
+    present: fn(P) -> std::result::Result<(), sdx_art_lib::Error>,
+) -> Result<()> {
+    match args.value_of("output") {
+        Some(output) => piece::export(Box::new(piece), Path::new(output))?,
+        None => present(piece)?,
+    }
 
-        if let Some(_args) = args.subcommand_matches("tiled_lines") {
-            let size = _args.value_of("size").unwrap().parse::<u32>().context("Can not parse size argument!")?;
-            let steps = _args.value_of("steps").unwrap().parse::<i32>().context("Can not parse steps argument!")?;
+    Ok(())
+}
 
-            generative_artistry::tiled_lines::present(size, steps);
+/// Shows the piece in the `watch` file with `watch` if one is given, otherwise exports or shows
+/// it like [`show`].
+fn show_watched<P: Piece + 'static>(
+    args: &ArgMatches,
+    piece: P,
+    present: fn(P) -> std::result::Result<(), sdx_art_lib::Error>,
+    watch: fn(P, &Path) -> std::result::Result<(), sdx_art_lib::Error>,
+) -> Result<()> {
+    match args.value_of("watch") {
+        Some(path) => {
+            ensure_not_exporting(args, Path::new(path))?;
+            watch(piece, Path::new(path))?;
         }
+        None => show(args, piece, present)?,
     }
 
-    if let Some(_args) = matches.subcommand_matches("sdx") {
-        sdx_art::first_sketch::present();
+    Ok(())
+}
+
+/// Watched files are shown in a window, they can't be exported at the same time.
+fn ensure_not_exporting(args: &ArgMatches, watched: &Path) -> Result<()> {
+    if args.is_present("output") {
+        bail!("Can not export while watching {}.", watched.display());
     }
 
     Ok(())
 }
+
+/// Builds the piece of a `ga` sketch from its arguments.
+fn ga_piece(sketch: &str, args: &ArgMatches) -> Result<Box<dyn Piece>> {
+    Ok(match sketch {
+        "circle_packing" => Box::new(circle_packing(args)?),
+        "tiled_lines" => Box::new(tiled_lines(args)?),
+        "joy_division" => Box::new(joy_division(args)?),
+        "cubic_disarray" => Box::new(cubic_disarray(args)?),
+        "triangular_mesh" => Box::new(triangular_mesh(args)?),
+        "un_deux_trois" => Box::new(un_deux_trois(args)?),
+        "hypnotic_squares" => Box::new(hypnotic_squares(args)?),
+        "mondrian" => Box::new(mondrian(args)?),
+        "unlikely_squares" => Box::new(unlikely_squares(args)?),
+        _ => unreachable!("{} is not a ga sketch", sketch),
+    })
+}
+
+fn circle_packing(args: &ArgMatches) -> Result<CirclePackingDescriptor> {
+    let width = args
+        .value_of("width")
+        .unwrap()
+        .parse::<u32>()
+        .context("Failed to parse width arg into a u32 type.")?;
+    let height = args
+        .value_of("height")
+        .unwrap()
+        .parse::<u32>()
+        .context("Failed to parse height arg into a u32 type.")?;
+    let line_width = args
+        .value_of("line_width")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse line_width arg into a f32 type.")?;
+    let min_radius = args
+        .value_of("min_radius")
+        .unwrap()
+        .parse::<usize>()
+        .context("Failed to parse min_radius arg into a usize type.")?;
+    let max_radius = args
+        .value_of("max_radius")
+        .unwrap()
+        .parse::<usize>()
+        .context("Failed to parse max_radius arg into a usize type.")?;
+    let total_circles = args
+        .value_of("total_circles")
+        .unwrap()
+        .parse::<usize>()
+        .context("Failed to parse total_circles arg into a usize type.")?;
+    let create_circle_attempts = args
+        .value_of("create_circle_attempts")
+        .unwrap()
+        .parse::<usize>()
+        .context("Failed to parse create_circle_attempts arg into a usize type.")?;
+
+    let seed = parse_seed(args)?;
+    let sketchy = args.is_present("sketchy").then(|| Sketchy::new(seed));
+
+    let descriptor = CirclePackingDescriptor::new(
+        line_width,
+        min_radius,
+        max_radius,
+        total_circles,
+        create_circle_attempts,
+        seed,
+        sketchy,
+    )
+    .with_size(width, height);
+    let descriptor = match args.value_of("load") {
+        Some(path) => descriptor.with_packing(
+            Packing::load(path)
+                .with_context(|| format!("Failed to load circles from {}.", path))?,
+        ),
+        None => descriptor,
+    };
+
+    Ok(descriptor)
+}
+
+fn tiled_lines(_args: &ArgMatches) -> Result<TiledLinesDescriptor> {
+    let size = _args.value_of("size").unwrap().parse::<u32>().context("Can not parse size argument!")?;
+    let width = _args.value_of("width").map(|w| w.parse::<u32>()).transpose().context("Can not parse width argument!")?.unwrap_or(size);
+    let height = _args.value_of("height").map(|h| h.parse::<u32>()).transpose().context("Can not parse height argument!")?.unwrap_or(size);
+    let steps = _args.value_of("steps").unwrap().parse::<u32>().context("Can not parse steps argument!")?.max(1);
+    let columns = _args.value_of("columns").map(|c| c.parse::<usize>()).transpose().context("Can not parse columns argument!")?
+        .unwrap_or_else(|| width.div_ceil(steps) as usize);
+    let rows = _args.value_of("rows").map(|r| r.parse::<usize>()).transpose().context("Can not parse rows argument!")?
+        .unwrap_or_else(|| height.div_ceil(steps) as usize);
+    let max_depth = _args.value_of("depth").unwrap().parse::<usize>().context("Can not parse depth argument!")?;
+    let split_probability = _args.value_of("split").unwrap().parse::<f32>().context("Can not parse split argument!")?;
+
+    let grid = match _args.value_of("grid").unwrap() {
+        "hex" => Grid::Hexagonal { radius: steps as f32 },
+        "tri" => Grid::Triangular { side: steps as f32 },
+        "quad" => Grid::Subdivided { columns, rows, max_depth, split_probability },
+        _ => Grid::Rectangular { columns, rows },
+    };
+
+    let scale = _args.value_of("scale").unwrap().parse::<f32>().context("Can not parse scale argument!")?;
+    let field_seed = _args.value_of("field_seed").unwrap().parse::<u32>().context("Can not parse field-seed argument!")?;
+    let threshold = _args.value_of("threshold").unwrap().parse::<f32>().context("Can not parse threshold argument!")?;
+
+    let mapping = match _args.value_of("mapping").unwrap() {
+        "probability" => FieldMapping::Probability,
+        _ => FieldMapping::Threshold(threshold),
+    };
+    let field = match _args.value_of("field").unwrap() {
+        "perlin" => Some(FieldSource::Perlin { scale, seed: field_seed }),
+        "simplex" => Some(FieldSource::Simplex { scale, seed: field_seed }),
+        "value" => Some(FieldSource::Value { scale, seed: field_seed }),
+        "radial" => Some(FieldSource::Radial { center: pt2(0.5, 0.5), radius: 0.5_f32.hypot(0.5) }),
+        "image" => Some(FieldSource::Image { path: PathBuf::from(_args.value_of("image").unwrap()) }),
+        "expression" => Some(FieldSource::Expression { expression: _args.value_of("expression").unwrap().to_string() }),
+        _ => None,
+    };
+    let orientation = match field {
+        Some(field) => Orientation::Field(field, mapping),
+        None => Orientation::Random,
+    };
+
+    let weight = _args.value_of("weight").unwrap().parse::<f32>().context("Can not parse weight argument!")?;
+    let color = _args.value_of("color").unwrap();
+    let color = color::from_hex(color).with_context(|| format!("Can not parse color {}!", color))?;
+    let cap = match _args.value_of("cap").unwrap() {
+        "round" => Cap::Round,
+        "square" => Cap::Square,
+        _ => Cap::Butt,
+    };
+    let stroke = StrokeStyle::new(color, weight).with_cap(cap);
+
+    let seed = parse_seed(_args)?;
+    let sketchy = _args.is_present("sketchy").then(|| Sketchy::new(seed));
+
+    Ok(TiledLinesDescriptor::new(width, height, grid, orientation, stroke, seed, sketchy))
+}
+
+fn joy_division(args: &ArgMatches) -> Result<JoyDivisionDescriptor> {
+    let size = args
+        .value_of("size")
+        .unwrap()
+        .parse::<u32>()
+        .context("Failed to parse size arg into a u32 type.")?;
+    let lines = args
+        .value_of("lines")
+        .unwrap()
+        .parse::<usize>()
+        .context("Failed to parse lines arg into a usize type.")?;
+    let points = args
+        .value_of("points")
+        .unwrap()
+        .parse::<usize>()
+        .context("Failed to parse points arg into a usize type.")?;
+    let amplitude = args
+        .value_of("amplitude")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse amplitude arg into a f32 type.")?;
+    let distortion = args
+        .value_of("distortion")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse distortion arg into a f32 type.")?;
+    let line_width = args
+        .value_of("line_width")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse line_width arg into a f32 type.")?;
+    let seed = parse_seed(args)?;
+
+    let descriptor = JoyDivisionDescriptor::new(
+        size, lines, points, amplitude, distortion, line_width, seed,
+    );
+
+    Ok(descriptor)
+}
+
+fn cubic_disarray(args: &ArgMatches) -> Result<CubicDisarrayDescriptor> {
+    let size = args
+        .value_of("size")
+        .unwrap()
+        .parse::<u32>()
+        .context("Failed to parse size arg into a u32 type.")?;
+    let square_size = args
+        .value_of("square_size")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse square_size arg into a f32 type.")?;
+    let displacement = args
+        .value_of("displacement")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse displacement arg into a f32 type.")?;
+    let rotation = args
+        .value_of("rotation")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse rotation arg into a f32 type.")?;
+    let line_width = args
+        .value_of("line_width")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse line_width arg into a f32 type.")?;
+    let falloff = match args.value_of("falloff").unwrap() {
+        "quadratic" => Falloff::Quadratic,
+        "exponential" => Falloff::Exponential,
+        _ => Falloff::Linear,
+    };
+    let seed = parse_seed(args)?;
+
+    let descriptor = CubicDisarrayDescriptor::new(
+        size,
+        square_size,
+        displacement,
+        rotation,
+        falloff,
+        line_width,
+        seed,
+    );
+
+    Ok(descriptor)
+}
+
+fn triangular_mesh(args: &ArgMatches) -> Result<TriangularMeshDescriptor> {
+    let size = args
+        .value_of("size")
+        .unwrap()
+        .parse::<u32>()
+        .context("Failed to parse size arg into a u32 type.")?;
+    let spacing = args
+        .value_of("spacing")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse spacing arg into a f32 type.")?;
+    let jitter = args
+        .value_of("jitter")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse jitter arg into a f32 type.")?;
+    let line_width = args
+        .value_of("line_width")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse line_width arg into a f32 type.")?;
+    let palette = parse_palette(args);
+    let seed = parse_seed(args)?;
+
+    let descriptor = TriangularMeshDescriptor::new(
+        size, spacing, jitter, palette, line_width, seed,
+    );
+
+    Ok(descriptor)
+}
+
+fn un_deux_trois(args: &ArgMatches) -> Result<UnDeuxTroisDescriptor> {
+    let size = args
+        .value_of("size")
+        .unwrap()
+        .parse::<u32>()
+        .context("Failed to parse size arg into a u32 type.")?;
+    let step = args
+        .value_of("step")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse step arg into a f32 type.")?;
+    let spacing = args
+        .value_of("spacing")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse spacing arg into a f32 type.")?;
+    let line_width = args
+        .value_of("line_width")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse line_width arg into a f32 type.")?;
+    let bands = parse_list(args, "bands")?;
+    let rotations = parse_list(args, "rotations")?;
+    let seed = parse_seed(args)?;
+
+    let descriptor = UnDeuxTroisDescriptor::new(
+        size, step, bands, rotations, spacing, line_width, seed,
+    );
+
+    Ok(descriptor)
+}
+
+fn hypnotic_squares(args: &ArgMatches) -> Result<HypnoticSquaresDescriptor> {
+    let size = args
+        .value_of("size")
+        .unwrap()
+        .parse::<u32>()
+        .context("Failed to parse size arg into a u32 type.")?;
+    let tiles = args
+        .value_of("tiles")
+        .unwrap()
+        .parse::<usize>()
+        .context("Failed to parse tiles arg into a usize type.")?;
+    let depth = args
+        .value_of("depth")
+        .unwrap()
+        .parse::<usize>()
+        .context("Failed to parse depth arg into a usize type.")?;
+    let min_size = args
+        .value_of("min_size")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse min_size arg into a f32 type.")?;
+    let offset = args
+        .value_of("offset")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse offset arg into a f32 type.")?;
+    let line_width = args
+        .value_of("line_width")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse line_width arg into a f32 type.")?;
+    let seed = parse_seed(args)?;
+
+    let descriptor = HypnoticSquaresDescriptor::new(
+        size, tiles, depth, min_size, offset, line_width, seed,
+    );
+
+    Ok(descriptor)
+}
+
+fn mondrian(args: &ArgMatches) -> Result<MondrianDescriptor> {
+    let size = args
+        .value_of("size")
+        .unwrap()
+        .parse::<u32>()
+        .context("Failed to parse size arg into a u32 type.")?;
+    let step = args
+        .value_of("step")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse step arg into a f32 type.")?;
+    let split_chance = args
+        .value_of("split_chance")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse split_chance arg into a f32 type.")?;
+    let colored = args
+        .value_of("colored")
+        .unwrap()
+        .parse::<usize>()
+        .context("Failed to parse colored arg into a usize type.")?;
+    let line_width = args
+        .value_of("line_width")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse line_width arg into a f32 type.")?;
+    let seed = parse_seed(args)?;
+
+    let descriptor = MondrianDescriptor::new(
+        size,
+        step,
+        split_chance,
+        colored,
+        line_width,
+        seed,
+    );
+
+    Ok(descriptor)
+}
+
+fn unlikely_squares(args: &ArgMatches) -> Result<UnlikelySquaresDescriptor> {
+    let size = args
+        .value_of("size")
+        .unwrap()
+        .parse::<u32>()
+        .context("Failed to parse size arg into a u32 type.")?;
+    let cells = args
+        .value_of("cells")
+        .unwrap()
+        .parse::<usize>()
+        .context("Failed to parse cells arg into a usize type.")?;
+    let depth = args
+        .value_of("depth")
+        .unwrap()
+        .parse::<usize>()
+        .context("Failed to parse depth arg into a usize type.")?;
+    let split_probability = args
+        .value_of("split_probability")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse split_probability arg into a f32 type.")?;
+    let fill_chance = args
+        .value_of("fill_chance")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse fill_chance arg into a f32 type.")?;
+    let line_width = args
+        .value_of("line_width")
+        .unwrap()
+        .parse::<f32>()
+        .context("Failed to parse line_width arg into a f32 type.")?;
+    let palette = parse_palette(args);
+    let seed = parse_seed(args)?;
+
+    let descriptor = UnlikelySquaresDescriptor::new(
+        size,
+        depth,
+        split_probability,
+        palette,
+        fill_chance,
+        line_width,
+        seed,
+    )
+    .with_cells(cells);
+
+    Ok(descriptor)
+}
+
+fn parse_seed(args: &ArgMatches) -> Result<u64> {
+    match args.value_of("seed") {
+        Some(seed) => seed.parse::<u64>().context("Can not parse seed argument!"),
+        None => Ok(nannou::rand::random()),
+    }
+}
+
+fn parse_palette(args: &ArgMatches) -> Palette {
+    Palette::from_name(args.value_of("palette").unwrap()).unwrap_or(Palette::Grayscale)
+}
+
+fn parse_list(args: &ArgMatches, name: &str) -> Result<Vec<f32>> {
+    args.value_of(name)
+        .unwrap()
+        .split(',')
+        .map(|value| {
+            value
+                .trim()
+                .parse::<f32>()
+                .with_context(|| format!("Failed to parse {} arg into a list of f32.", name))
+        })
+        .collect()
+}
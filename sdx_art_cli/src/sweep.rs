@@ -0,0 +1,506 @@
+use std::ffi::OsString;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{App, AppSettings, Arg, ArgMatches, ArgSettings};
+
+use generative_artistry::contact_sheet::ContactSheet;
+use generative_artistry::piece::{self, Piece};
+use sdx_art_lib::contact_sheet::Layout;
+
+use crate::config;
+
+/// Size of the cells of the `--sheet` contact sheet.
+const SHEET_CELL: f32 = 256.0;
+/// Most pieces a sweep renders. Every combination is built before rendering starts, so larger
+/// sweeps, usually a mistyped range, are rejected up front rather than failing late.
+const MAX_RENDERS: usize = 10_000;
+
+/// Builds the piece of a sketch from its parsed arguments.
+pub type Build = fn(&str, &ArgMatches) -> Result<Box<dyn Piece>>;
+
+/// A sketch argument and all values to render it with, flags having a single `None` value.
+struct Parameter {
+    long: String,
+    values: Vec<Option<String>>,
+}
+
+/// A piece of the sweep, with the file it's rendered to and a caption for the contact sheet.
+struct Render {
+    piece: Box<dyn Piece>,
+    path: PathBuf,
+    caption: String,
+}
+
+pub fn app() -> App<'static> {
+    App::new("sweep")
+        .about("Render every combination of parameter values of a ga sketch into a directory")
+        .setting(AppSettings::TrailingVarArg)
+        .setting(AppSettings::AllowHyphenValues)
+        .arg(
+            Arg::new("dir")
+                .help("Directory to render into, along with an index.html contact sheet")
+                .long("dir")
+                .value_name("DIR")
+                .takes_value(true)
+                .default_value("sweep"),
+        )
+        .arg(
+            Arg::new("sheet")
+                .help("Also lay all renders out on one captioned .png or .svg contact sheet")
+                .long("sheet")
+                .value_name("FILE")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("jobs")
+                .help("Number of pieces rendered in parallel, all cores are used if omitted")
+                .long("jobs")
+                .value_name("JOBS")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("sketch")
+                .help(
+                    "Sketch to render, e.g. tiled_lines. \
+                     Options of the sweep itself go before it.",
+                )
+                .value_name("SKETCH")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("parameters")
+                .help(
+                    "Arguments of the sketch. Values may be ranges like 10..40:5 or 0..1:0.25, \
+                     the step defaulting to 1, and arguments may be repeated to list values, \
+                     e.g. --grid rect --grid hex.",
+                )
+                .value_name("PARAMETERS")
+                .takes_value(true)
+                .multiple_values(true)
+                .allow_hyphen_values(true),
+        )
+}
+
+/// Renders all combinations of the sweep parameters headlessly to SVGs, with metadata sidecars,
+/// and writes a contact sheet of them. The config file and preset apply to every render.
+pub fn run(app: App<'static>, args: &ArgMatches, build: Build) -> Result<()> {
+    let sketch = args.value_of("sketch").unwrap();
+    let sketch_app = app
+        .find_subcommand("ga")
+        .and_then(|ga| ga.find_subcommand(sketch))
+        .ok_or_else(|| anyhow!("Unknown sketch {}.", sketch))?;
+    let parameters = parse_parameters(
+        sketch_app,
+        args.values_of("parameters").into_iter().flatten(),
+    )?;
+    let dir = Path::new(args.value_of("dir").unwrap());
+    let jobs = match args.value_of("jobs") {
+        Some(jobs) => jobs
+            .parse::<usize>()
+            .context("Failed to parse jobs arg into a usize type.")?,
+        None => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+    };
+    if jobs == 0 {
+        bail!("At least one job is needed to render.");
+    }
+
+    let count = parameters
+        .iter()
+        .try_fold(1usize, |count, p| count.checked_mul(p.values.len()));
+    match count {
+        Some(count) if count <= MAX_RENDERS => {}
+        _ => bail!(
+            "The sweep has more than {} combinations, try fewer values.",
+            MAX_RENDERS
+        ),
+    }
+    let combinations = combinations(&parameters);
+    let digits = combinations.len().to_string().len();
+    let mut renders = vec![];
+    for (i, combination) in combinations.iter().enumerate() {
+        let mut sketch_args: Vec<OsString> = vec![app.get_name().into()];
+        for global in ["config", "preset"] {
+            if let Some(value) = args.value_of(global) {
+                sketch_args.push(format!("--{}={}", global, value).into());
+            }
+        }
+        sketch_args.push("ga".into());
+        sketch_args.push(sketch.into());
+        sketch_args.extend(combination.iter().map(OsString::from));
+
+        let invalid = || format!("Invalid parameters {}.", combination.join(" "));
+        let matches = config::get_matches_from(app.clone(), sketch_args).with_context(invalid)?;
+        let matches = matches
+            .subcommand_matches("ga")
+            .and_then(|ga| ga.subcommand_matches(sketch))
+            .expect("the sketch is always given");
+        let piece = build(sketch, matches)?;
+        piece.validate().with_context(invalid)?;
+
+        let mut caption: Vec<&str> = combination
+            .iter()
+            .map(|arg| arg.trim_start_matches('-'))
+            .collect();
+        // Random seeds are drawn per render, so they're worth knowing.
+        let seed = format!("seed={}", piece.seed());
+        if !caption.iter().any(|arg| arg.starts_with("seed=")) {
+            caption.push(&seed);
+        }
+
+        renders.push(Render {
+            caption: caption.join(" "),
+            path: dir.join(format!("{}-{:0width$}.svg", sketch, i + 1, width = digits)),
+            piece,
+        });
+    }
+
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}.", dir.display()))?;
+    let html = contact_sheet(sketch, &renders);
+    let count = renders.len();
+    let sheet = ContactSheet::new(
+        renders
+            .iter()
+            .map(|render| render.piece.metadata())
+            .collect(),
+        Layout::new(count, SHEET_CELL).with_caption_size(SHEET_CELL / 16.0),
+    )
+    .with_captions(
+        renders
+            .iter()
+            .map(|render| render.caption.clone())
+            .collect(),
+    );
+    render_all(renders, jobs)?;
+
+    let index = dir.join("index.html");
+    fs::write(&index, html).with_context(|| format!("Failed to write {}.", index.display()))?;
+    println!("Rendered {} pieces, see {}.", count, index.display());
+
+    if let Some(path) = args.value_of("sheet") {
+        piece::export(Box::new(sheet), Path::new(path))?;
+    }
+
+    Ok(())
+}
+
+/// Parses the sketch arguments given to the sweep, expanding ranges and merging repeated ones.
+/// Like in config files, arguments may also be given by name, e.g. `--max-radius`.
+fn parse_parameters<'a>(
+    app: &App<'static>,
+    args: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<Parameter>> {
+    let mut parameters: Vec<Parameter> = vec![];
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (key, value) = match arg.split_once('=') {
+            Some((key, value)) => (key, Some(value)),
+            None => (arg, None),
+        };
+        let found = match (key.strip_prefix("--"), key.strip_prefix('-')) {
+            (Some(long), _) => config::find_arg(app, long),
+            (None, Some(short)) if short.chars().count() == 1 => app
+                .get_arguments()
+                .find(|a| a.get_short() == short.chars().next()),
+            _ => None,
+        };
+        let found = found.ok_or_else(|| anyhow!("Unknown parameter {}.", key))?;
+        let long = found
+            .get_long()
+            .ok_or_else(|| anyhow!("Parameter {} can't be swept.", key))?;
+
+        let values = if found.is_set(ArgSettings::TakesValue) {
+            let spec = match value {
+                Some(value) => value,
+                None => args
+                    .next()
+                    .ok_or_else(|| anyhow!("Parameter {} needs a value.", key))?,
+            };
+            expand(spec)
+                .with_context(|| format!("Invalid values {} of parameter {}.", spec, key))?
+                .into_iter()
+                .map(Some)
+                .collect()
+        } else if value.is_none() {
+            vec![None]
+        } else {
+            bail!("Parameter {} is a flag and takes no value.", key);
+        };
+
+        match parameters.iter_mut().find(|p| p.long == long) {
+            Some(parameter) if found.is_set(ArgSettings::TakesValue) => {
+                parameter.values.extend(values)
+            }
+            Some(_) => {}
+            None => parameters.push(Parameter {
+                long: long.to_string(),
+                values,
+            }),
+        }
+    }
+
+    Ok(parameters)
+}
+
+/// Expands an inclusive range `start..end:step` into its values, returning anything else, like
+/// file paths, as the single value. Ranges of more than [`MAX_RENDERS`] values are rejected.
+fn expand(spec: &str) -> Result<Vec<String>> {
+    let (range, step) = match spec.rsplit_once(':') {
+        Some((range, step)) => (range, Some(step)),
+        None => (spec, None),
+    };
+    let (start, end) = match range.split_once("..") {
+        Some(bounds) => bounds,
+        None => return Ok(vec![spec.to_string()]),
+    };
+
+    let integers = (
+        start.parse::<i64>(),
+        end.parse::<i64>(),
+        step.map_or(Ok(1), str::parse::<i64>),
+    );
+    if let (Ok(start), Ok(end), Ok(step)) = integers {
+        if step < 1 {
+            bail!("The step must be positive.");
+        }
+        if start > end {
+            bail!("The range is empty.");
+        }
+        ensure_countable((end as i128 - start as i128) / step as i128 + 1)?;
+
+        return Ok((start..=end)
+            .step_by(step as usize)
+            .map(|value| value.to_string())
+            .collect());
+    }
+
+    let floats = (
+        start.parse::<f64>(),
+        end.parse::<f64>(),
+        step.map_or(Ok(1.0), str::parse::<f64>),
+    );
+    match floats {
+        (Ok(start), Ok(end), Ok(step)) => {
+            if step <= 0.0 {
+                bail!("The step must be positive.");
+            }
+            if start > end {
+                bail!("The range is empty.");
+            }
+
+            // The epsilon keeps the end value despite rounding errors of the step.
+            let count = ((end - start) / step + 1e-9).floor() + 1.0;
+            if !count.is_finite() {
+                bail!("The range must be finite.");
+            }
+            let count = ensure_countable(count as i128)?;
+            Ok((0..count)
+                .map(|i| {
+                    let value = start + i as f64 * step;
+                    // Rounded, so 0.1 steps don't end up as 0.30000000000000004.
+                    ((value * 1e9).round() / 1e9).to_string()
+                })
+                .collect())
+        }
+        _ => Ok(vec![spec.to_string()]),
+    }
+}
+
+/// Bails if a range of `count` values is more than a sweep renders.
+fn ensure_countable(count: i128) -> Result<usize> {
+    if count > MAX_RENDERS as i128 {
+        bail!(
+            "The range has {} values, a sweep renders at most {}.",
+            count,
+            MAX_RENDERS
+        );
+    }
+
+    Ok(count as usize)
+}
+
+/// All combinations of parameter values as sketch arguments, e.g. `["--steps=10", "--sketchy"]`.
+fn combinations(parameters: &[Parameter]) -> Vec<Vec<String>> {
+    parameters
+        .iter()
+        .fold(vec![vec![]], |combinations, parameter| {
+            combinations
+                .iter()
+                .flat_map(|combination| {
+                    parameter.values.iter().map(move |value| {
+                        let mut combination = combination.clone();
+                        combination.push(match value {
+                            Some(value) => format!("--{}={}", parameter.long, value),
+                            None => format!("--{}", parameter.long),
+                        });
+                        combination
+                    })
+                })
+                .collect()
+        })
+}
+
+/// Renders the pieces on `jobs` threads, each taking the next piece once done with one.
+fn render_all(renders: Vec<Render>, jobs: usize) -> Result<()> {
+    let queue = Mutex::new(renders.into_iter());
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let next = || queue.lock().unwrap().next();
+                    while let Some(render) = next() {
+                        piece::export(render.piece, &render.path).with_context(|| {
+                            format!("Failed to render {}.", render.path.display())
+                        })?;
+                    }
+
+                    Ok(())
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .try_for_each(|worker| worker.join().expect("render thread panicked"))
+    })
+}
+
+/// HTML page showing all renders of the sweep in a grid, captioned with their parameters.
+fn contact_sheet(sketch: &str, renders: &[Render]) -> String {
+    let figures: String = renders
+        .iter()
+        .map(|render| {
+            let file = render
+                .path
+                .file_name()
+                .expect("renders are files")
+                .to_string_lossy();
+
+            format!(
+                "<figure><a href=\"{0}\"><img src=\"{0}\" loading=\"lazy\"></a>\
+                 <figcaption>{1}</figcaption></figure>\n",
+                escape(&file),
+                escape(&render.caption)
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{0} sweep</title>
+<style>
+body {{ font-family: sans-serif; margin: 1em; background: #ddd; }}
+main {{ display: grid; grid-template-columns: repeat(auto-fill, minmax(240px, 1fr)); gap: 1em; }}
+figure {{ margin: 0; padding: 0.5em; background: #fff; }}
+img {{ display: block; width: 100%; height: auto; }}
+figcaption {{ margin-top: 0.5em; font-size: 0.8em; overflow-wrap: anywhere; }}
+</style>
+</head>
+<body>
+<h1>{0}</h1>
+<main>
+{1}</main>
+</body>
+</html>
+"#,
+        escape(sketch),
+        figures
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameter(long: &str, values: &[Option<&str>]) -> Parameter {
+        Parameter {
+            long: long.to_string(),
+            values: values.iter().map(|v| v.map(String::from)).collect(),
+        }
+    }
+
+    #[test]
+    fn expands_integer_ranges() {
+        assert_eq!(expand("1..4").unwrap(), ["1", "2", "3", "4"]);
+        assert_eq!(expand("10..20:5").unwrap(), ["10", "15", "20"]);
+        assert_eq!(expand("-2..3:2").unwrap(), ["-2", "0", "2"]);
+        assert_eq!(expand("3..3").unwrap(), ["3"]);
+    }
+
+    #[test]
+    fn expands_float_ranges() {
+        assert_eq!(expand("0..0.3:0.1").unwrap(), ["0", "0.1", "0.2", "0.3"]);
+        assert_eq!(expand("0.5..2").unwrap(), ["0.5", "1.5"]);
+        assert_eq!(expand("1..2:0.4").unwrap(), ["1", "1.4", "1.8"]);
+    }
+
+    #[test]
+    fn keeps_other_values() {
+        assert_eq!(expand("hex").unwrap(), ["hex"]);
+        assert_eq!(
+            expand("../images/photo.png").unwrap(),
+            ["../images/photo.png"]
+        );
+        assert_eq!(expand("a..b").unwrap(), ["a..b"]);
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        assert!(expand("1..5:0").is_err());
+        assert!(expand("1..5:-1").is_err());
+        assert!(expand("5..1").is_err());
+        assert!(expand("0..1:0.0").is_err());
+        assert!(expand("1.5..0.5").is_err());
+        assert!(expand("0..1e400").is_err());
+    }
+
+    #[test]
+    fn rejects_ranges_larger_than_a_sweep() {
+        assert_eq!(expand("1..10000").unwrap().len(), MAX_RENDERS);
+        assert!(expand("1..10001").is_err());
+        assert!(expand("0..1000:0.1").is_err());
+        assert!(expand(&format!("{}..{}", i64::MIN, i64::MAX)).is_err());
+    }
+
+    #[test]
+    fn combines_every_value() {
+        let parameters = [
+            parameter("steps", &[Some("5"), Some("10")]),
+            parameter("sketchy", &[None]),
+            parameter("grid", &[Some("square"), Some("hex")]),
+        ];
+
+        assert_eq!(
+            combinations(&parameters),
+            [
+                ["--steps=5", "--sketchy", "--grid=square"],
+                ["--steps=5", "--sketchy", "--grid=hex"],
+                ["--steps=10", "--sketchy", "--grid=square"],
+                ["--steps=10", "--sketchy", "--grid=hex"],
+            ]
+        );
+    }
+
+    #[test]
+    fn combines_nothing_into_one_render() {
+        assert_eq!(combinations(&[]), [Vec::<String>::new()]);
+        assert!(combinations(&[parameter("steps", &[])]).is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+use nannou::prelude::*;
+
+use crate::stroke::{dashes, variable_width_outline, Cap, Join, StrokeStyle};
+
+pub mod raster;
+pub mod svg;
+pub mod viewport;
+
+/// Drawing backend the shapes and sketches render to.
+///
+/// Implemented by nannou's [`Draw`] for on screen previews, and by [`svg::SvgCanvas`] and
+/// [`raster::RasterCanvas`] for exports. Backends only need to provide solid primitives;
+/// dashes and variable widths are resolved by [`Canvas::stroke_path`] on top of them.
+pub trait Canvas {
+    fn fill_background(&self, color: Rgba);
+
+    fn fill_polygon(&self, points: &[Point2], color: Rgba);
+
+    /// Strokes a polyline with the uniform weight, caps and joins of `style`, ignoring its
+    /// dash pattern and widths.
+    fn stroke_polyline(&self, points: &[Point2], style: &StrokeStyle);
+
+    /// Fills a single line of sans serif text of `size` pixels, centered in `rect`.
+    fn fill_text(&self, text: &str, rect: Rect, size: f32, color: Rgba);
+
+    fn stroke_path(&self, points: &[Point2], style: &StrokeStyle) {
+        if points.len() < 2 {
+            return;
+        }
+
+        let segments = if style.is_dashed() {
+            dashes(points, &style.dash)
+        } else {
+            vec![points.to_vec()]
+        };
+
+        for segment in segments {
+            if style.is_variable_width() {
+                self.fill_polygon(&variable_width_outline(&segment, style), style.color);
+            } else {
+                self.stroke_polyline(&segment, style);
+            }
+        }
+    }
+
+    fn stroke_line(&self, start: Point2, end: Point2, style: &StrokeStyle) {
+        self.stroke_path(&[start, end], style);
+    }
+}
+
+impl Canvas for Draw {
+    fn fill_background(&self, color: Rgba) {
+        self.background().color(color);
+    }
+
+    fn fill_polygon(&self, points: &[Point2], color: Rgba) {
+        if points.len() < 3 {
+            return;
+        }
+
+        self.polygon().color(color).points(points.iter().copied());
+    }
+
+    fn stroke_polyline(&self, points: &[Point2], style: &StrokeStyle) {
+        let drawing = self.polyline().weight(style.weight).color(style.color);
+        let drawing = match style.cap {
+            Cap::Butt => drawing.caps_butt(),
+            Cap::Round => drawing.caps_round(),
+            Cap::Square => drawing.caps_square(),
+        };
+        let drawing = match style.join {
+            Join::Miter => drawing.join_miter(),
+            Join::Round => drawing.join_round(),
+            Join::Bevel => drawing.join_bevel(),
+        };
+
+        drawing.points(points.iter().copied());
+    }
+
+    fn fill_text(&self, text: &str, rect: Rect, size: f32, color: Rgba) {
+        self.text(text)
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .font_size(size.round() as u32)
+            .no_line_wrap()
+            .center_justify()
+            .align_text_middle_y()
+            .color(color);
+    }
+}
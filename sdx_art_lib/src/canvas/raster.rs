@@ -0,0 +1,389 @@
+use std::cell::RefCell;
+use std::f32::consts::TAU;
+
+use nannou::image::png::PngEncoder;
+use nannou::image::ColorType;
+use nannou::prelude::*;
+use rusttype::{point, Font, Scale};
+
+use crate::canvas::Canvas;
+use crate::error::Error;
+use crate::stroke::{Cap, Join, StrokeStyle};
+
+/// Sub-scanlines sampled per pixel row, horizontal coverage being computed exactly.
+const SAMPLES: usize = 4;
+/// Longest miter, relative to the stroke weight, before falling back to a bevel as SVG does.
+const MITER_LIMIT: f32 = 4.0;
+
+/// Canvas rasterizing everything drawn onto it in memory, so PNGs can be exported without
+/// opening a window.
+///
+/// Uses the same coordinate system as nannou, the origin being the center of `rect` and y
+/// pointing up. Shapes are antialiased and filled with the nonzero rule, text is set in Noto
+/// Sans like nannou does.
+pub struct RasterCanvas {
+    rect: Rect,
+    width: usize,
+    height: usize,
+    /// Straight, not premultiplied, RGBA pixels row by row from the top.
+    pixels: RefCell<Vec<[f32; 4]>>,
+    font: Option<Font<'static>>,
+}
+
+impl RasterCanvas {
+    pub fn new(rect: Rect) -> RasterCanvas {
+        let width = rect.w().round().max(1.0) as usize;
+        let height = rect.h().round().max(1.0) as usize;
+
+        RasterCanvas {
+            rect,
+            width,
+            height,
+            pixels: RefCell::new(vec![[0.0; 4]; width * height]),
+            font: Font::from_bytes(notosans::REGULAR_TTF).ok(),
+        }
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>, Error> {
+        let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let bytes: Vec<u8> = self
+            .pixels
+            .borrow()
+            .iter()
+            .flat_map(|pixel| pixel.map(channel))
+            .collect();
+
+        let mut png = vec![];
+        PngEncoder::new(&mut png)
+            .encode(
+                &bytes,
+                self.width as u32,
+                self.height as u32,
+                ColorType::Rgba8,
+            )
+            .map_err(|e| Error::Serialize {
+                format: "PNG",
+                message: e.to_string(),
+            })?;
+
+        Ok(png)
+    }
+
+    /// Converts canvas coordinates into pixel coordinates, y pointing down.
+    fn to_pixels(&self, point: Point2) -> Vec2 {
+        vec2(point.x - self.rect.left(), self.rect.top() - point.y)
+    }
+
+    /// Fills the area enclosed by `paths` with the nonzero rule, so overlapping paths running
+    /// the same way are blended only once.
+    fn fill_paths(&self, paths: &[Vec<Point2>], color: Rgba) {
+        let edges: Vec<(Vec2, Vec2)> = paths
+            .iter()
+            .filter(|path| path.len() > 2)
+            .flat_map(|path| {
+                let points: Vec<Vec2> = path.iter().map(|p| self.to_pixels(*p)).collect();
+                (0..points.len()).map(move |i| (points[i], points[(i + 1) % points.len()]))
+            })
+            .filter(|(a, b)| a.y != b.y)
+            .collect();
+        if edges.is_empty() {
+            return;
+        }
+
+        let (top, bottom) = edges
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(top, bottom), (a, b)| {
+                (top.min(a.y).min(b.y), bottom.max(a.y).max(b.y))
+            });
+        let first_row = top.floor().max(0.0) as usize;
+        let last_row = (bottom.ceil().max(0.0) as usize).min(self.height);
+
+        let mut coverage = vec![0.0; self.width];
+        let mut crossings = vec![];
+        for row in first_row..last_row {
+            coverage.iter_mut().for_each(|c| *c = 0.0);
+
+            for sample in 0..SAMPLES {
+                let y = row as f32 + (sample as f32 + 0.5) / SAMPLES as f32;
+
+                crossings.clear();
+                for (a, b) in &edges {
+                    let winding = if a.y <= y && y < b.y {
+                        1
+                    } else if b.y <= y && y < a.y {
+                        -1
+                    } else {
+                        continue;
+                    };
+                    crossings.push((a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x), winding));
+                }
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                let mut winding = 0;
+                for pair in crossings.windows(2) {
+                    winding += pair[0].1;
+                    if winding != 0 {
+                        add_span(&mut coverage, pair[0].0, pair[1].0, 1.0 / SAMPLES as f32);
+                    }
+                }
+            }
+
+            for (x, c) in coverage.iter().enumerate() {
+                if *c > 0.0 {
+                    self.blend(x, row, color, c.min(1.0));
+                }
+            }
+        }
+    }
+
+    /// Blends `color` over the pixel, `coverage` being the fraction of the pixel it covers.
+    fn blend(&self, x: usize, y: usize, color: Rgba, coverage: f32) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        let mut pixels = self.pixels.borrow_mut();
+        let pixel = &mut pixels[y * self.width + x];
+        let alpha = color.alpha.clamp(0.0, 1.0) * coverage;
+        let below = pixel[3] * (1.0 - alpha);
+        let total = alpha + below;
+        if total <= 0.0 {
+            return;
+        }
+
+        for (channel, value) in pixel.iter_mut().zip([color.red, color.green, color.blue]) {
+            *channel = (value * alpha + *channel * below) / total;
+        }
+        pixel[3] = total;
+    }
+}
+
+impl Canvas for RasterCanvas {
+    fn fill_background(&self, color: Rgba) {
+        let pixel = [color.red, color.green, color.blue, color.alpha];
+        self.pixels.borrow_mut().iter_mut().for_each(|p| *p = pixel);
+    }
+
+    fn fill_polygon(&self, points: &[Point2], color: Rgba) {
+        self.fill_paths(&[points.to_vec()], color);
+    }
+
+    fn stroke_polyline(&self, points: &[Point2], style: &StrokeStyle) {
+        self.fill_paths(&stroke_outline(points, style), style.color);
+    }
+
+    fn fill_text(&self, text: &str, rect: Rect, size: f32, color: Rgba) {
+        let font = match &self.font {
+            Some(font) => font,
+            None => return,
+        };
+
+        let scale = Scale::uniform(size);
+        let metrics = font.v_metrics(scale);
+        let width = font
+            .layout(text, scale, point(0.0, 0.0))
+            .last()
+            .map_or(0.0, |glyph| {
+                glyph.position().x + glyph.unpositioned().h_metrics().advance_width
+            });
+
+        let center = self.to_pixels(rect.xy());
+        let baseline = center.y + (metrics.ascent + metrics.descent) / 2.0;
+        for glyph in font.layout(text, scale, point(center.x - width / 2.0, baseline)) {
+            if let Some(bounds) = glyph.pixel_bounding_box() {
+                glyph.draw(|x, y, coverage| {
+                    let x = bounds.min.x + x as i32;
+                    let y = bounds.min.y + y as i32;
+                    if x >= 0 && y >= 0 {
+                        self.blend(x as usize, y as usize, color, coverage);
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Adds `weight` times the part of each pixel covered by the span from `start` to `end`.
+fn add_span(coverage: &mut [f32], start: f32, end: f32, weight: f32) {
+    let start = start.max(0.0);
+    let end = end.min(coverage.len() as f32);
+
+    let mut x = start.floor();
+    while x < end {
+        let covered = end.min(x + 1.0) - start.max(x);
+        coverage[x as usize] += covered * weight;
+        x += 1.0;
+    }
+}
+
+/// Outline of a stroked polyline as closed paths running counterclockwise, one per segment,
+/// join and cap.
+fn stroke_outline(points: &[Point2], style: &StrokeStyle) -> Vec<Vec<Point2>> {
+    let mut points = points.to_vec();
+    points.dedup();
+    let half = style.weight / 2.0;
+    if points.len() < 2 || half <= 0.0 {
+        return vec![];
+    }
+
+    let last = points.len() - 1;
+    let direction = |i: usize| (points[i + 1] - points[i]).normalize();
+    let mut paths = vec![];
+
+    for i in 0..last {
+        let (mut start, mut end) = (points[i], points[i + 1]);
+        let d = direction(i);
+        if style.cap == Cap::Square {
+            if i == 0 {
+                start -= d * half;
+            }
+            if i + 1 == last {
+                end += d * half;
+            }
+        }
+
+        let n = d.perp() * half;
+        paths.push(vec![start - n, end - n, end + n, start + n]);
+    }
+
+    for (i, &vertex) in points.iter().enumerate().take(last).skip(1) {
+        let (d_in, d_out) = (direction(i - 1), direction(i));
+        let turn = d_in.perp_dot(d_out);
+        if turn.abs() < 1e-6 && d_in.dot(d_out) > 0.0 {
+            continue;
+        }
+
+        // The outer side of the corner is right of left turns and left of right turns.
+        let side = if turn > 0.0 { -half } else { half };
+        let outer_in = vertex + d_in.perp() * side;
+        let outer_out = vertex + d_out.perp() * side;
+        let bisector = (d_in.perp() + d_out.perp()).normalize_or_zero();
+        let cosine = bisector.dot(d_in.perp());
+
+        match style.join {
+            Join::Round => paths.push(circle(vertex, half)),
+            Join::Miter if cosine > 0.0 && 1.0 / cosine <= MITER_LIMIT => {
+                let miter = vertex + bisector * side / cosine;
+                paths.push(vec![vertex, outer_in, miter, outer_out]);
+            }
+            Join::Miter | Join::Bevel => paths.push(vec![vertex, outer_in, outer_out]),
+        }
+    }
+
+    if style.cap == Cap::Round {
+        paths.push(circle(points[0], half));
+        paths.push(circle(points[last], half));
+    }
+
+    paths.into_iter().map(counterclockwise).collect()
+}
+
+fn circle(center: Point2, radius: f32) -> Vec<Point2> {
+    let segments = ((radius.sqrt() * 8.0) as usize).clamp(12, 96);
+
+    (0..segments)
+        .map(|i| {
+            let angle = i as f32 / segments as f32 * TAU;
+            center + vec2(angle.cos(), angle.sin()) * radius
+        })
+        .collect()
+}
+
+fn counterclockwise(mut path: Vec<Point2>) -> Vec<Point2> {
+    let area: f32 = (0..path.len())
+        .map(|i| path[i].perp_dot(path[(i + 1) % path.len()]))
+        .sum();
+    if area < 0.0 {
+        path.reverse();
+    }
+
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgba = Rgba {
+        color: Rgb {
+            red: 0.0,
+            green: 0.0,
+            blue: 0.0,
+            standard: std::marker::PhantomData,
+        },
+        alpha: 1.0,
+    };
+
+    fn pixel(canvas: &RasterCanvas, x: usize, y: usize) -> [f32; 4] {
+        canvas.pixels.borrow()[y * canvas.width + x]
+    }
+
+    #[test]
+    fn fills_polygons_with_antialiased_edges() {
+        let canvas = RasterCanvas::new(Rect::from_w_h(10.0, 10.0));
+        canvas.fill_background(Rgba::new(1.0, 1.0, 1.0, 1.0));
+        // Covers the left half of the canvas and half of the pixel column at x = 5.
+        canvas.fill_polygon(
+            &[
+                pt2(-5.0, -5.0),
+                pt2(0.5, -5.0),
+                pt2(0.5, 5.0),
+                pt2(-5.0, 5.0),
+            ],
+            BLACK,
+        );
+
+        assert_eq!(pixel(&canvas, 0, 0), [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(pixel(&canvas, 4, 9), [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(pixel(&canvas, 5, 5), [0.5, 0.5, 0.5, 1.0]);
+        assert_eq!(pixel(&canvas, 6, 5), [1.0, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn blends_overlapping_stroke_parts_once() {
+        let canvas = RasterCanvas::new(Rect::from_w_h(20.0, 20.0));
+        canvas.fill_background(Rgba::new(1.0, 1.0, 1.0, 1.0));
+        let mut style = StrokeStyle::new(Rgba::new(0.0, 0.0, 0.0, 0.5), 4.0);
+        style.join = Join::Round;
+        style.cap = Cap::Round;
+        canvas.stroke_polyline(&[pt2(-8.0, 0.0), pt2(0.0, 0.0), pt2(0.0, 8.0)], &style);
+
+        // The corner is covered by both segments and the round join.
+        assert_eq!(pixel(&canvas, 10, 10), [0.5, 0.5, 0.5, 1.0]);
+        assert_eq!(pixel(&canvas, 4, 10), [0.5, 0.5, 0.5, 1.0]);
+        assert_eq!(pixel(&canvas, 15, 15), [1.0, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn outlines_run_counterclockwise() {
+        let mut style = StrokeStyle::new(BLACK, 2.0);
+        style.cap = Cap::Square;
+        let outline = stroke_outline(&[pt2(0.0, 0.0), pt2(10.0, 0.0), pt2(10.0, -10.0)], &style);
+
+        assert_eq!(outline.len(), 3);
+        assert_eq!(
+            outline[0],
+            [
+                pt2(-1.0, -1.0),
+                pt2(10.0, -1.0),
+                pt2(10.0, 1.0),
+                pt2(-1.0, 1.0)
+            ]
+        );
+        // The miter of the right turn sticks out at the top right.
+        assert!(outline[2].contains(&pt2(11.0, 1.0)));
+    }
+
+    #[test]
+    fn encodes_pngs() {
+        let canvas = RasterCanvas::new(Rect::from_w_h(3.0, 2.0));
+        let png = canvas.to_png().unwrap();
+
+        assert!(png.starts_with(&[0x89, b'P', b'N', b'G']));
+        assert_eq!(&png[16..24], [0, 0, 0, 3, 0, 0, 0, 2]);
+    }
+}
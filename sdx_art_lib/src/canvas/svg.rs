@@ -0,0 +1,145 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use nannou::prelude::*;
+
+use crate::canvas::Canvas;
+use crate::color::to_hex;
+use crate::stroke::{Cap, Join, StrokeStyle};
+
+/// Canvas recording everything drawn onto it as SVG elements.
+///
+/// Uses the same coordinate system as nannou, the origin being the center of `rect` and y
+/// pointing up, so sketches render identically to both backends.
+pub struct SvgCanvas {
+    rect: Rect,
+    elements: RefCell<Vec<String>>,
+}
+
+impl SvgCanvas {
+    pub fn new(rect: Rect) -> SvgCanvas {
+        SvgCanvas {
+            rect,
+            elements: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn to_svg(&self) -> String {
+        self.document(None)
+    }
+
+    /// Like [`SvgCanvas::to_svg`], with `metadata` escaped into a `<metadata>` element.
+    pub fn to_svg_with_metadata(&self, metadata: &str) -> String {
+        self.document(Some(metadata))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_svg())
+    }
+
+    fn document(&self, metadata: Option<&str>) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = self.rect.w(),
+            h = self.rect.h()
+        );
+        if let Some(metadata) = metadata {
+            svg.push_str(&format!("  <metadata>{}</metadata>\n", escape(metadata)));
+        }
+        for element in self.elements.borrow().iter() {
+            svg.push_str("  ");
+            svg.push_str(element);
+            svg.push('\n');
+        }
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+
+    fn push(&self, element: String) {
+        self.elements.borrow_mut().push(element);
+    }
+
+    fn points_attribute(&self, points: &[Point2]) -> String {
+        points
+            .iter()
+            .map(|p| format!("{:.2},{:.2}", p.x - self.rect.left(), self.rect.top() - p.y))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+impl Canvas for SvgCanvas {
+    fn fill_background(&self, color: Rgba) {
+        self.elements.borrow_mut().clear();
+        self.push(format!(
+            "<rect width=\"100%\" height=\"100%\" fill=\"{}\" fill-opacity=\"{}\"/>",
+            to_hex(color),
+            color.alpha
+        ));
+    }
+
+    fn fill_polygon(&self, points: &[Point2], color: Rgba) {
+        if points.len() < 3 {
+            return;
+        }
+
+        self.push(format!(
+            "<polygon points=\"{}\" fill=\"{}\" fill-opacity=\"{}\"/>",
+            self.points_attribute(points),
+            to_hex(color),
+            color.alpha
+        ));
+    }
+
+    fn stroke_polyline(&self, points: &[Point2], style: &StrokeStyle) {
+        if points.len() < 2 {
+            return;
+        }
+
+        let cap = match style.cap {
+            Cap::Butt => "butt",
+            Cap::Round => "round",
+            Cap::Square => "square",
+        };
+        let join = match style.join {
+            Join::Miter => "miter",
+            Join::Round => "round",
+            Join::Bevel => "bevel",
+        };
+
+        self.push(format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-opacity=\"{}\" stroke-width=\"{}\" stroke-linecap=\"{}\" stroke-linejoin=\"{}\"/>",
+            self.points_attribute(points),
+            to_hex(style.color),
+            style.color.alpha,
+            style.weight,
+            cap,
+            join
+        ));
+    }
+
+    fn fill_text(&self, text: &str, rect: Rect, size: f32, color: Rgba) {
+        self.push(format!(
+            "<text x=\"{:.2}\" y=\"{:.2}\" font-family=\"sans-serif\" font-size=\"{}\" fill=\"{}\" fill-opacity=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+            rect.x() - self.rect.left(),
+            self.rect.top() - rect.y(),
+            size,
+            to_hex(color),
+            color.alpha,
+            escape(text)
+        ));
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,73 @@
+use nannou::prelude::*;
+
+use crate::canvas::Canvas;
+use crate::stroke::StrokeStyle;
+
+/// Canvas wrapper drawing a render of `source` scaled down or up to fit into `target`, keeping
+/// its aspect ratio and centering it.
+///
+/// Weights and text sizes scale along, and backgrounds only fill the fitted rect. Nothing is
+/// clipped though, so drawings reaching beyond `source` do so beyond the fitted rect as well.
+pub struct Viewport<'a> {
+    canvas: &'a dyn Canvas,
+    source: Rect,
+    /// Where `source` ends up on the wrapped canvas.
+    fitted: Rect,
+    scale: f32,
+}
+
+impl<'a> Viewport<'a> {
+    pub fn new(canvas: &'a dyn Canvas, source: Rect, target: Rect) -> Viewport<'a> {
+        let scale = (target.w() / source.w()).min(target.h() / source.h());
+        let fitted = Rect::from_xy_wh(target.xy(), source.wh() * scale);
+
+        Viewport {
+            canvas,
+            source,
+            fitted,
+            scale,
+        }
+    }
+
+    /// Part of the wrapped canvas the source is drawn to.
+    pub fn fitted(&self) -> Rect {
+        self.fitted
+    }
+
+    fn map(&self, point: Point2) -> Point2 {
+        self.fitted.xy() + (point - self.source.xy()) * self.scale
+    }
+
+    fn map_all(&self, points: &[Point2]) -> Vec<Point2> {
+        points.iter().map(|p| self.map(*p)).collect()
+    }
+}
+
+impl<'a> Canvas for Viewport<'a> {
+    fn fill_background(&self, color: Rgba) {
+        let corners = [
+            self.fitted.top_left(),
+            self.fitted.top_right(),
+            self.fitted.bottom_right(),
+            self.fitted.bottom_left(),
+        ];
+        self.canvas.fill_polygon(&corners, color);
+    }
+
+    fn fill_polygon(&self, points: &[Point2], color: Rgba) {
+        self.canvas.fill_polygon(&self.map_all(points), color);
+    }
+
+    fn stroke_polyline(&self, points: &[Point2], style: &StrokeStyle) {
+        let style = StrokeStyle {
+            weight: style.weight * self.scale,
+            ..style.clone()
+        };
+        self.canvas.stroke_polyline(&self.map_all(points), &style);
+    }
+
+    fn fill_text(&self, text: &str, rect: Rect, size: f32, color: Rgba) {
+        let rect = Rect::from_xy_wh(self.map(rect.xy()), rect.wh() * self.scale);
+        self.canvas.fill_text(text, rect, size * self.scale, color);
+    }
+}
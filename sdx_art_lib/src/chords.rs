@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+
+use nannou::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Ways of connecting the vertices of a figure with chords.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChordPattern {
+    /// Each vertex connected to the opposite one, or the one just short of it for odd counts.
+    Diagonals,
+    /// The {n/k} star polygon, connecting each vertex to the `k`th next one.
+    Star(usize),
+    /// Multiplication table circle, connecting vertex `i` to vertex `i * m` modulo the count.
+    TimesTable(usize),
+    /// Every vertex connected to every other one.
+    Complete,
+    /// Vertex `i` connected to the vertex the `i`th skip of the repeating sequence ahead.
+    Skips(Vec<usize>),
+    /// No chords at all.
+    None,
+}
+
+impl ChordPattern {
+    /// Chords between `vertices`, which are expected not to repeat the first vertex at the end.
+    /// Each pair of vertices is connected at most once.
+    pub fn chords(&self, vertices: &[Point2]) -> Vec<(Point2, Point2)> {
+        let n = vertices.len();
+        if n < 2 {
+            return vec![];
+        }
+
+        // Reduced modulo n before adding or multiplying, so large values from configs and scene
+        // files can't overflow.
+        let pairs: Vec<(usize, usize)> = match self {
+            ChordPattern::Diagonals => (0..n).map(|i| (i, (i + n / 2) % n)).collect(),
+            ChordPattern::Star(k) => (0..n).map(|i| (i, (i + k % n) % n)).collect(),
+            ChordPattern::TimesTable(m) => (0..n).map(|i| (i, i * (m % n) % n)).collect(),
+            ChordPattern::Complete => (0..n)
+                .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+                .collect(),
+            ChordPattern::Skips(skips) if !skips.is_empty() => (0..n)
+                .map(|i| (i, (i + skips[i % skips.len()] % n) % n))
+                .collect(),
+            ChordPattern::Skips(_) | ChordPattern::None => vec![],
+        };
+
+        let mut seen = HashSet::new();
+
+        pairs
+            .into_iter()
+            .filter(|(a, b)| a != b && seen.insert((*a.min(b), *a.max(b))))
+            .map(|(a, b)| (vertices[a], vertices[b]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Indices of the vertices connected by the chords of `pattern` between `n` vertices.
+    fn pairs(pattern: ChordPattern, n: usize) -> Vec<(usize, usize)> {
+        let vertices: Vec<Point2> = (0..n).map(|i| pt2(i as f32, 0.0)).collect();
+
+        pattern
+            .chords(&vertices)
+            .into_iter()
+            .map(|(a, b)| (a.x as usize, b.x as usize))
+            .collect()
+    }
+
+    #[test]
+    fn connects_vertices_by_pattern() {
+        assert_eq!(
+            pairs(ChordPattern::Diagonals, 5),
+            [(0, 2), (1, 3), (2, 4), (3, 0), (4, 1)]
+        );
+        assert_eq!(
+            pairs(ChordPattern::Star(2), 5),
+            [(0, 2), (1, 3), (2, 4), (3, 0), (4, 1)]
+        );
+        assert_eq!(
+            pairs(ChordPattern::TimesTable(2), 5),
+            [(1, 2), (2, 4), (3, 1), (4, 3)]
+        );
+        assert_eq!(
+            pairs(ChordPattern::Complete, 4),
+            [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]
+        );
+        assert_eq!(
+            pairs(ChordPattern::Skips(vec![1, 2]), 4),
+            [(0, 1), (1, 3), (2, 3)]
+        );
+        assert_eq!(pairs(ChordPattern::None, 4), []);
+    }
+
+    #[test]
+    fn connects_each_pair_once() {
+        // Opposite vertices of even counts would otherwise be connected in both directions.
+        assert_eq!(pairs(ChordPattern::Diagonals, 4), [(0, 2), (1, 3)]);
+        assert_eq!(pairs(ChordPattern::Star(2), 4), [(0, 2), (1, 3)]);
+        // Vertices aren't connected to themselves.
+        assert_eq!(pairs(ChordPattern::Star(4), 4), []);
+    }
+
+    #[test]
+    fn reduces_large_values_without_overflowing() {
+        assert_eq!(
+            pairs(ChordPattern::Star(usize::MAX), 5),
+            pairs(ChordPattern::Star(usize::MAX % 5), 5)
+        );
+        assert_eq!(
+            pairs(ChordPattern::TimesTable(usize::MAX), 5),
+            pairs(ChordPattern::TimesTable(usize::MAX % 5), 5)
+        );
+        assert_eq!(
+            pairs(ChordPattern::Skips(vec![usize::MAX]), 5),
+            pairs(ChordPattern::Skips(vec![usize::MAX % 5]), 5)
+        );
+    }
+
+    #[test]
+    fn connects_nothing_without_two_vertices_or_skips() {
+        assert_eq!(pairs(ChordPattern::Complete, 0), []);
+        assert_eq!(pairs(ChordPattern::Complete, 1), []);
+        assert_eq!(pairs(ChordPattern::Skips(vec![]), 5), []);
+    }
+}
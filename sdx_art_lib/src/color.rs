@@ -0,0 +1,39 @@
+use nannou::prelude::*;
+
+/// Parses `#rrggbb` or `#rrggbbaa` hex colors, the leading `#` being optional.
+pub fn from_hex(hex: &str) -> Option<Rgba> {
+    let hex = hex.trim().trim_start_matches('#');
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |i: usize| {
+        hex.get(i..i + 2)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+            .map(|c| c as f32 / 255.0)
+    };
+    let alpha = if hex.len() == 8 { channel(6)? } else { 1.0 };
+
+    Some(Rgba::new(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+/// Formats the color channels as `#rrggbb`, dropping the alpha channel.
+pub fn to_hex(color: Rgba) -> String {
+    let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(color.red),
+        channel(color.green),
+        channel(color.blue)
+    )
+}
+
+/// Formats all color channels as `#rrggbbaa`.
+pub fn to_hex_alpha(color: Rgba) -> String {
+    format!(
+        "{}{:02x}",
+        to_hex(color),
+        (color.alpha.clamp(0.0, 1.0) * 255.0).round() as u8
+    )
+}
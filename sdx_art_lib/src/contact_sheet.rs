@@ -0,0 +1,216 @@
+use nannou::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::canvas::viewport::Viewport;
+use crate::canvas::Canvas;
+use crate::error::{ensure_at_least, ensure_non_negative, ensure_positive, Error};
+
+/// Draws a render onto the canvas within the given rect.
+pub type Render<'a> = Box<dyn Fn(&dyn Canvas, Rect) -> Result<(), Error> + 'a>;
+
+/// One render laid out on a contact sheet.
+pub struct Entry<'a> {
+    /// Canvas rect the render expects, e.g. of its window size.
+    pub rect: Rect,
+    pub caption: Option<String>,
+    pub render: Render<'a>,
+}
+
+/// Grid of equally sized cells, filled row by row, each render being scaled to fit its cell.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Layout {
+    pub columns: usize,
+    pub cell_width: f32,
+    pub cell_height: f32,
+    /// Space between the cells and around the grid.
+    pub gap: f32,
+    /// Text size of the captions below the cells, no room is left for captions if 0.
+    pub caption_size: f32,
+}
+
+impl Layout {
+    /// Square cells in as many columns as needed for the grid to be about square.
+    pub fn new(count: usize, cell_size: f32) -> Layout {
+        Layout {
+            columns: (count as f32).sqrt().ceil().max(1.0) as usize,
+            cell_width: cell_size,
+            cell_height: cell_size,
+            gap: (cell_size / 16.0).round(),
+            caption_size: 0.0,
+        }
+    }
+
+    pub fn with_columns(self, columns: usize) -> Layout {
+        Layout { columns, ..self }
+    }
+
+    pub fn with_gap(self, gap: f32) -> Layout {
+        Layout { gap, ..self }
+    }
+
+    pub fn with_caption_size(self, caption_size: f32) -> Layout {
+        Layout {
+            caption_size,
+            ..self
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("columns", self.columns, 1)?;
+        ensure_positive("cell width", self.cell_width)?;
+        ensure_positive("cell height", self.cell_height)?;
+        ensure_non_negative("gap", self.gap)?;
+        ensure_non_negative("caption size", self.caption_size)
+    }
+
+    pub fn rows(&self, count: usize) -> usize {
+        count.div_ceil(self.columns).max(1)
+    }
+
+    /// Size of a sheet of `count` renders.
+    pub fn size(&self, count: usize) -> (u32, u32) {
+        let columns = self.columns.min(count).max(1);
+        let width = columns as f32 * (self.cell_width + self.gap) + self.gap;
+        let height = self.rows(count) as f32 * (self.row_height() + self.gap) + self.gap;
+
+        (width.ceil() as u32, height.ceil() as u32)
+    }
+
+    /// Rects of the `i`th render and of its caption, within a sheet at `rect`.
+    pub fn cell(&self, rect: Rect, i: usize) -> (Rect, Rect) {
+        let (column, row) = (i % self.columns, i / self.columns);
+        let left = rect.left() + self.gap + column as f32 * (self.cell_width + self.gap);
+        let top = rect.top() - self.gap - row as f32 * (self.row_height() + self.gap);
+
+        let cell = Rect::from_corners(
+            pt2(left, top),
+            pt2(left + self.cell_width, top - self.cell_height),
+        );
+        let caption = Rect::from_corners(
+            pt2(left, cell.bottom()),
+            pt2(cell.right(), top - self.row_height()),
+        );
+
+        (cell, caption)
+    }
+
+    fn caption_height(&self) -> f32 {
+        self.caption_size * 2.0
+    }
+
+    fn row_height(&self) -> f32 {
+        self.cell_height + self.caption_height()
+    }
+}
+
+/// Draws a contact sheet of `entries` onto `canvas`, the grid starting at the top left of
+/// `rect`. Captions are only drawn if the layout leaves room for them.
+///
+/// Stops at the first render failing, returning its error.
+pub fn draw(
+    canvas: &dyn Canvas,
+    rect: Rect,
+    layout: &Layout,
+    entries: &[Entry],
+) -> Result<(), Error> {
+    canvas.fill_background(Rgba::new(0.85, 0.85, 0.85, 1.0));
+
+    for (i, entry) in entries.iter().enumerate() {
+        let (cell, caption_rect) = layout.cell(rect, i);
+        let viewport = Viewport::new(canvas, entry.rect, cell);
+        (entry.render)(&viewport, entry.rect)?;
+
+        if let Some(caption) = entry
+            .caption
+            .as_deref()
+            .filter(|_| layout.caption_size > 0.0)
+        {
+            canvas.fill_text(
+                caption,
+                caption_rect,
+                layout.caption_size,
+                Rgba::new(0.0, 0.0, 0.0, 1.0),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::canvas::svg::SvgCanvas;
+
+    fn edges(rect: Rect) -> [f32; 4] {
+        [rect.left(), rect.top(), rect.right(), rect.bottom()]
+    }
+
+    #[test]
+    fn sizes_sheets_to_fit_the_renders() {
+        let layout = Layout::new(5, 100.0);
+        assert_eq!((layout.columns, layout.gap), (3, 6.0));
+
+        assert_eq!(layout.size(5), (324, 218));
+        assert_eq!(layout.size(3), (324, 112));
+        // Fewer renders than columns only take up as many columns as needed.
+        assert_eq!(layout.size(2), (218, 112));
+        assert_eq!(layout.size(0), (112, 112));
+        assert_eq!(layout.with_caption_size(10.0).size(5), (324, 258));
+        // Fractional sizes are rounded up, so nothing gets cut off.
+        assert_eq!(layout.with_gap(0.5).size(5), (302, 202));
+    }
+
+    #[test]
+    fn lays_cells_out_row_by_row() {
+        let layout = Layout::new(5, 100.0);
+        let rect = Rect::from_w_h(324.0, 218.0);
+
+        let (cell, caption) = layout.cell(rect, 0);
+        assert_eq!(edges(cell), [-156.0, 103.0, -56.0, 3.0]);
+        assert_eq!(edges(caption), [-156.0, 3.0, -56.0, 3.0]);
+
+        let (cell, _) = layout.cell(rect, 4);
+        assert_eq!(edges(cell), [-50.0, -3.0, 50.0, -103.0]);
+    }
+
+    #[test]
+    fn leaves_room_for_captions_below_cells() {
+        let layout = Layout::new(5, 100.0)
+            .with_columns(2)
+            .with_caption_size(10.0);
+        let (width, height) = layout.size(5);
+        assert_eq!((width, height), (218, 384));
+        let rect = Rect::from_w_h(width as f32, height as f32);
+
+        let (cell, caption) = layout.cell(rect, 3);
+        assert_eq!(edges(cell), [3.0, 60.0, 103.0, -40.0]);
+        assert_eq!(edges(caption), [3.0, -40.0, 103.0, -60.0]);
+    }
+
+    #[test]
+    fn stops_at_the_first_failing_render() {
+        let drawn = &Cell::new(0);
+        let entry = |fails: bool| Entry {
+            rect: Rect::from_w_h(10.0, 10.0),
+            caption: None,
+            render: Box::new(move |_, _| {
+                drawn.set(drawn.get() + 1);
+                if fails {
+                    return Err(Error::invalid("render", "fails"));
+                }
+                Ok(())
+            }),
+        };
+        let entries = [entry(false), entry(true), entry(false)];
+        let layout = Layout::new(entries.len(), 10.0);
+        let (width, height) = layout.size(entries.len());
+        let rect = Rect::from_w_h(width as f32, height as f32);
+
+        let drawn_sheet = draw(&SvgCanvas::new(rect), rect, &layout, &entries);
+        assert!(matches!(drawn_sheet, Err(Error::InvalidParameter { .. })));
+        assert_eq!(drawn.get(), 2);
+    }
+}
@@ -0,0 +1,418 @@
+use nannou::prelude::*;
+
+/// Deepest subdivision used when flattening, limiting a segment to 1/2^16 of the curve.
+const MAX_DEPTH: usize = 16;
+/// Subdivisions done regardless of the tolerance, so S-shaped spans whose middle happens to
+/// lie on the chord are not mistaken for straight ones.
+const MIN_DEPTH: usize = 3;
+
+/// A smooth parametric curve running from `t = 0` to `t = 1`.
+pub trait Curve {
+    fn point(&self, t: f32) -> Point2;
+
+    /// First derivative with respect to `t`.
+    fn derivative(&self, t: f32) -> Vec2;
+
+    /// Unit direction of travel at `t`.
+    fn tangent(&self, t: f32) -> Vec2 {
+        self.derivative(t).normalize_or_zero()
+    }
+
+    /// Unit vector pointing to the left of the direction of travel at `t`.
+    fn normal(&self, t: f32) -> Vec2 {
+        self.tangent(t).perp()
+    }
+
+    /// Polyline whose segments stay within `tolerance` of the curve.
+    fn flatten(&self, tolerance: f32) -> Vec<Point2> {
+        flatten_parameters(self, tolerance)
+            .into_iter()
+            .map(|t| self.point(t))
+            .collect()
+    }
+
+    /// Arc length, measured along the polyline flattened with `tolerance`.
+    fn length(&self, tolerance: f32) -> f32 {
+        self.flatten(tolerance)
+            .windows(2)
+            .map(|segment| segment[0].distance(segment[1]))
+            .sum()
+    }
+
+    /// Parameter at which the arc length from the start equals `distance`, clamped to the
+    /// curve.
+    fn t_at_length(&self, distance: f32, tolerance: f32) -> f32 {
+        let parameters = flatten_parameters(self, tolerance);
+        let mut travelled = 0.0;
+
+        for pair in parameters.windows(2) {
+            let length = self.point(pair[0]).distance(self.point(pair[1]));
+            if travelled + length >= distance {
+                let fraction = (distance - travelled) / length.max(f32::EPSILON);
+                return pair[0] + (pair[1] - pair[0]) * fraction.clamp(0.0, 1.0);
+            }
+            travelled += length;
+        }
+
+        if distance <= 0.0 {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    /// `count` points evenly spaced by arc length, including both ends.
+    fn points_by_length(&self, count: usize, tolerance: f32) -> Vec<Point2> {
+        let length = self.length(tolerance);
+        let segments = count.max(2) - 1;
+
+        (0..=segments)
+            .map(|i| {
+                let distance = length * i as f32 / segments as f32;
+                self.point(self.t_at_length(distance, tolerance))
+            })
+            .collect()
+    }
+
+    /// Flattened curve moved by `distance` along its normals, to the left for positive
+    /// distances.
+    fn offset(&self, distance: f32, tolerance: f32) -> Vec<Point2> {
+        flatten_parameters(self, tolerance)
+            .into_iter()
+            .map(|t| self.point(t) + self.normal(t) * distance)
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct QuadraticBezier {
+    pub start: Point2,
+    pub control: Point2,
+    pub end: Point2,
+}
+
+impl QuadraticBezier {
+    pub fn new(start: Point2, control: Point2, end: Point2) -> QuadraticBezier {
+        QuadraticBezier {
+            start,
+            control,
+            end,
+        }
+    }
+}
+
+impl Curve for QuadraticBezier {
+    fn point(&self, t: f32) -> Point2 {
+        let u = 1.0 - t;
+
+        self.start * u * u + self.control * 2.0 * u * t + self.end * t * t
+    }
+
+    fn derivative(&self, t: f32) -> Vec2 {
+        (self.control - self.start) * 2.0 * (1.0 - t) + (self.end - self.control) * 2.0 * t
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CubicBezier {
+    pub start: Point2,
+    pub control1: Point2,
+    pub control2: Point2,
+    pub end: Point2,
+}
+
+impl CubicBezier {
+    pub fn new(start: Point2, control1: Point2, control2: Point2, end: Point2) -> CubicBezier {
+        CubicBezier {
+            start,
+            control1,
+            control2,
+            end,
+        }
+    }
+}
+
+impl Curve for CubicBezier {
+    fn point(&self, t: f32) -> Point2 {
+        let u = 1.0 - t;
+
+        self.start * u * u * u
+            + self.control1 * 3.0 * u * u * t
+            + self.control2 * 3.0 * u * t * t
+            + self.end * t * t * t
+    }
+
+    fn derivative(&self, t: f32) -> Vec2 {
+        let u = 1.0 - t;
+
+        (self.control1 - self.start) * 3.0 * u * u
+            + (self.control2 - self.control1) * 6.0 * u * t
+            + (self.end - self.control2) * 3.0 * t * t
+    }
+}
+
+/// Uniform Catmull-Rom spline passing through all `points`.
+#[derive(Clone, Debug)]
+pub struct CatmullRom {
+    pub points: Vec<Point2>,
+}
+
+impl CatmullRom {
+    pub fn new(points: Vec<Point2>) -> CatmullRom {
+        CatmullRom { points }
+    }
+
+    /// The four points shaping the segment `t` falls into and the parameter within it.
+    fn segment(&self, t: f32) -> ([Point2; 4], f32) {
+        let last = self.points.len() - 1;
+        let (i, u) = segment_at(t, last);
+        let at = |i: isize| self.points[i.clamp(0, last as isize) as usize];
+        let i = i as isize;
+
+        ([at(i - 1), at(i), at(i + 1), at(i + 2)], u)
+    }
+}
+
+impl Curve for CatmullRom {
+    fn point(&self, t: f32) -> Point2 {
+        match self.points.len() {
+            0 => pt2(0.0, 0.0),
+            1 => self.points[0],
+            _ => {
+                let ([p0, p1, p2, p3], u) = self.segment(t);
+
+                (p1 * 2.0
+                    + (p2 - p0) * u
+                    + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * u * u
+                    + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * u * u * u)
+                    * 0.5
+            }
+        }
+    }
+
+    fn derivative(&self, t: f32) -> Vec2 {
+        if self.points.len() < 2 {
+            return vec2(0.0, 0.0);
+        }
+
+        let ([p0, p1, p2, p3], u) = self.segment(t);
+        let segments = (self.points.len() - 1) as f32;
+
+        ((p2 - p0)
+            + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * 2.0 * u
+            + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * 3.0 * u * u)
+            * 0.5
+            * segments
+    }
+}
+
+/// Uniform cubic B-spline approximating its control `points`, clamped so it starts and ends
+/// at the first and last point.
+#[derive(Clone, Debug)]
+pub struct BSpline {
+    pub points: Vec<Point2>,
+}
+
+impl BSpline {
+    pub fn new(points: Vec<Point2>) -> BSpline {
+        BSpline { points }
+    }
+
+    /// Control points with both ends tripled, which pins the spline to them.
+    fn clamped(&self) -> Vec<Point2> {
+        let first = self.points[0];
+        let last = self.points[self.points.len() - 1];
+
+        [first, first]
+            .into_iter()
+            .chain(self.points.iter().copied())
+            .chain([last, last])
+            .collect()
+    }
+}
+
+impl Curve for BSpline {
+    fn point(&self, t: f32) -> Point2 {
+        if self.points.is_empty() {
+            return pt2(0.0, 0.0);
+        }
+
+        let q = self.clamped();
+        let (i, u) = segment_at(t, q.len() - 3);
+        let v = 1.0 - u;
+
+        (q[i] * v * v * v
+            + q[i + 1] * (3.0 * u * u * u - 6.0 * u * u + 4.0)
+            + q[i + 2] * (-3.0 * u * u * u + 3.0 * u * u + 3.0 * u + 1.0)
+            + q[i + 3] * u * u * u)
+            / 6.0
+    }
+
+    fn derivative(&self, t: f32) -> Vec2 {
+        if self.points.is_empty() {
+            return vec2(0.0, 0.0);
+        }
+
+        let q = self.clamped();
+        let segments = q.len() - 3;
+        let (i, u) = segment_at(t, segments);
+        let v = 1.0 - u;
+
+        (q[i] * -v * v
+            + q[i + 1] * (3.0 * u * u - 4.0 * u)
+            + q[i + 2] * (-3.0 * u * u + 2.0 * u + 1.0)
+            + q[i + 3] * u * u)
+            / 2.0
+            * segments as f32
+    }
+}
+
+/// Index of the segment out of `segments` equally long ones `t` falls into, and the
+/// parameter within it.
+fn segment_at(t: f32, segments: usize) -> (usize, f32) {
+    let scaled = t.clamp(0.0, 1.0) * segments as f32;
+    let i = (scaled.floor() as usize).min(segments - 1);
+
+    (i, scaled - i as f32)
+}
+
+/// Parameters of the polyline approximating `curve` within `tolerance`, found by halving
+/// spans until their middle lies close enough to the chord.
+fn flatten_parameters<C: Curve + ?Sized>(curve: &C, tolerance: f32) -> Vec<f32> {
+    let tolerance = tolerance.max(0.001);
+    let mut parameters = vec![0.0];
+
+    subdivide(curve, 0.0, 1.0, 0, tolerance, &mut parameters);
+
+    parameters
+}
+
+fn subdivide<C: Curve + ?Sized>(
+    curve: &C,
+    start: f32,
+    end: f32,
+    depth: usize,
+    tolerance: f32,
+    parameters: &mut Vec<f32>,
+) {
+    let middle = (start + end) / 2.0;
+
+    let flat = depth >= MIN_DEPTH && {
+        let (a, b) = (curve.point(start), curve.point(end));
+        distance_to_segment(curve.point(middle), a, b) <= tolerance
+    };
+
+    if flat || depth >= MAX_DEPTH {
+        parameters.push(end);
+    } else {
+        subdivide(curve, start, middle, depth + 1, tolerance, parameters);
+        subdivide(curve, middle, end, depth + 1, tolerance, parameters);
+    }
+}
+
+fn distance_to_segment(point: Point2, a: Point2, b: Point2) -> f32 {
+    let ab = b - a;
+    let t = ((point - a).dot(ab) / ab.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+
+    point.distance(a + ab * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: Point2, b: Point2) {
+        assert!(a.distance(b) < 1e-3, "{:?} is not {:?}", a, b);
+    }
+
+    #[test]
+    fn beziers_run_from_start_to_end() {
+        let quadratic = QuadraticBezier::new(pt2(0.0, 0.0), pt2(1.0, 2.0), pt2(2.0, 0.0));
+        assert_near(quadratic.point(0.0), pt2(0.0, 0.0));
+        assert_near(quadratic.point(0.5), pt2(1.0, 1.0));
+        assert_near(quadratic.point(1.0), pt2(2.0, 0.0));
+        assert_near(quadratic.tangent(0.5), vec2(1.0, 0.0));
+        assert_near(quadratic.normal(0.5), vec2(0.0, 1.0));
+
+        let cubic = CubicBezier::new(pt2(0.0, 0.0), pt2(0.0, 1.0), pt2(1.0, 1.0), pt2(1.0, 0.0));
+        assert_near(cubic.point(0.5), pt2(0.5, 0.75));
+        assert_near(cubic.derivative(0.0), vec2(0.0, 3.0));
+    }
+
+    #[test]
+    fn derivatives_match_finite_differences() {
+        let points = vec![pt2(0.0, 0.0), pt2(1.0, 2.0), pt2(3.0, -1.0), pt2(4.0, 1.0)];
+        let curves: [&dyn Curve; 2] = [&CatmullRom::new(points.clone()), &BSpline::new(points)];
+        let h = 1e-3;
+
+        for curve in curves {
+            for t in [0.1, 0.3, 0.6, 0.9] {
+                let estimate = (curve.point(t + h) - curve.point(t - h)) / (2.0 * h);
+                assert!(curve.derivative(t).distance(estimate) < 0.05);
+            }
+        }
+    }
+
+    #[test]
+    fn splines_hit_their_ends() {
+        let points = vec![pt2(0.0, 0.0), pt2(1.0, 1.0), pt2(2.0, 0.0)];
+
+        let catmull_rom = CatmullRom::new(points.clone());
+        assert_near(catmull_rom.point(0.0), points[0]);
+        assert_near(catmull_rom.point(0.5), points[1]);
+        assert_near(catmull_rom.point(1.0), points[2]);
+
+        let b_spline = BSpline::new(points.clone());
+        assert_near(b_spline.point(0.0), points[0]);
+        assert_near(b_spline.point(1.0), points[2]);
+    }
+
+    #[test]
+    fn measures_arc_length() {
+        let line = QuadraticBezier::new(pt2(0.0, 0.0), pt2(2.0, 0.0), pt2(4.0, 0.0));
+        assert!((line.length(0.01) - 4.0).abs() < 1e-3);
+        assert_near(line.point(line.t_at_length(1.0, 0.01)), pt2(1.0, 0.0));
+        assert_eq!(line.t_at_length(-1.0, 0.01), 0.0);
+        assert_eq!(line.t_at_length(5.0, 0.01), 1.0);
+
+        let points = line.points_by_length(5, 0.01);
+        assert_eq!(points.len(), 5);
+        for (i, point) in points.iter().enumerate() {
+            assert_near(*point, pt2(i as f32, 0.0));
+        }
+
+        // Speeding up along `t`, which arc length spacing has to undo.
+        let uneven = QuadraticBezier::new(pt2(0.0, 0.0), pt2(0.5, 0.0), pt2(4.0, 0.0));
+        for (i, point) in uneven.points_by_length(5, 0.01).iter().enumerate() {
+            assert!(
+                (point.x - i as f32).abs() < 0.05,
+                "{:?} is not at {}",
+                point,
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn flattens_within_tolerance() {
+        let arc = QuadraticBezier::new(pt2(0.0, 0.0), pt2(50.0, 100.0), pt2(100.0, 0.0));
+        let polyline = arc.flatten(0.5);
+
+        for i in 0..=100 {
+            let point = arc.point(i as f32 / 100.0);
+            let distance = polyline
+                .windows(2)
+                .map(|segment| distance_to_segment(point, segment[0], segment[1]))
+                .fold(f32::MAX, f32::min);
+            assert!(distance <= 0.5, "{:?} is {} off", point, distance);
+        }
+    }
+
+    #[test]
+    fn offsets_to_the_left() {
+        let line = QuadraticBezier::new(pt2(0.0, 0.0), pt2(1.0, 0.0), pt2(2.0, 0.0));
+        for point in line.offset(1.0, 0.1) {
+            assert!((point.y - 1.0).abs() < 1e-5);
+        }
+    }
+}
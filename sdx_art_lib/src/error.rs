@@ -0,0 +1,110 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Errors raised when shapes or sketches are set up with parameters they can't be drawn with,
+/// or when their description can't be read or written.
+#[derive(Debug)]
+pub enum Error {
+    /// A parameter is outside of its valid range.
+    InvalidParameter { name: &'static str, message: String },
+    /// A file couldn't be read or written.
+    Io { path: PathBuf, source: io::Error },
+    /// A description file isn't valid in the given format, e.g. `"TOML"`.
+    Parse {
+        format: &'static str,
+        message: String,
+    },
+    /// A value can't be written in the given format.
+    Serialize {
+        format: &'static str,
+        message: String,
+    },
+}
+
+impl Error {
+    pub fn invalid(name: &'static str, message: impl Into<String>) -> Error {
+        Error::InvalidParameter {
+            name,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidParameter { name, message } => write!(f, "invalid {}: {}", name, message),
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Error::Parse { format, message } => write!(f, "invalid {}: {}", format, message),
+            Error::Serialize { format, message } => {
+                write!(f, "can't write {}: {}", format, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+pub fn ensure_positive(name: &'static str, value: f32) -> Result<(), Error> {
+    if value > 0.0 && value.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::invalid(
+            name,
+            format!("must be positive, got {}", value),
+        ))
+    }
+}
+
+pub fn ensure_non_negative(name: &'static str, value: f32) -> Result<(), Error> {
+    if value >= 0.0 && value.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::invalid(
+            name,
+            format!("must not be negative, got {}", value),
+        ))
+    }
+}
+
+/// Ensures `value` lies within 0 and 1, e.g. for probabilities.
+pub fn ensure_unit(name: &'static str, value: f32) -> Result<(), Error> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(Error::invalid(
+            name,
+            format!("must be between 0 and 1, got {}", value),
+        ))
+    }
+}
+
+pub fn ensure_at_least(name: &'static str, value: usize, min: usize) -> Result<(), Error> {
+    if value >= min {
+        Ok(())
+    } else {
+        Err(Error::invalid(
+            name,
+            format!("must be at least {}, got {}", min, value),
+        ))
+    }
+}
+
+pub fn ensure_at_most(name: &'static str, value: usize, max: usize) -> Result<(), Error> {
+    if value <= max {
+        Ok(())
+    } else {
+        Err(Error::invalid(
+            name,
+            format!("must be at most {}, got {}", max, value),
+        ))
+    }
+}
@@ -0,0 +1,315 @@
+use std::f32::consts::PI;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use crate::error::Error;
+
+/// Arithmetic expression of a position, e.g. `sin(x * 10) * 0.5 + 0.5`, as used by expression
+/// fields.
+///
+/// Expressions combine numbers, `pi`, the variables `x` and `y` and the distance `r` from
+/// `(0.5, 0.5)` with `+`, `-`, `*`, `/`, `%` and `^`, as well as the functions `sin`, `cos`,
+/// `tan`, `abs`, `sqrt`, `floor`, `fract`, `exp`, `ln`, `min`, `max` and `pow`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expression {
+    root: Node,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Number(f32),
+    X,
+    Y,
+    R,
+    Negate(Box<Node>),
+    Binary(char, Box<Node>, Box<Node>),
+    Call(Function, Vec<Node>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Abs,
+    Sqrt,
+    Floor,
+    Fract,
+    Exp,
+    Ln,
+    Min,
+    Max,
+    Pow,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Function> {
+        match name {
+            "sin" => Some(Function::Sin),
+            "cos" => Some(Function::Cos),
+            "tan" => Some(Function::Tan),
+            "abs" => Some(Function::Abs),
+            "sqrt" => Some(Function::Sqrt),
+            "floor" => Some(Function::Floor),
+            "fract" => Some(Function::Fract),
+            "exp" => Some(Function::Exp),
+            "ln" => Some(Function::Ln),
+            "min" => Some(Function::Min),
+            "max" => Some(Function::Max),
+            "pow" => Some(Function::Pow),
+            _ => None,
+        }
+    }
+
+    fn arity(&self) -> usize {
+        match self {
+            Function::Min | Function::Max | Function::Pow => 2,
+            _ => 1,
+        }
+    }
+
+    fn apply(&self, args: &[f32]) -> f32 {
+        match self {
+            Function::Sin => args[0].sin(),
+            Function::Cos => args[0].cos(),
+            Function::Tan => args[0].tan(),
+            Function::Abs => args[0].abs(),
+            Function::Sqrt => args[0].sqrt(),
+            Function::Floor => args[0].floor(),
+            Function::Fract => args[0] - args[0].floor(),
+            Function::Exp => args[0].exp(),
+            Function::Ln => args[0].ln(),
+            Function::Min => args[0].min(args[1]),
+            Function::Max => args[0].max(args[1]),
+            Function::Pow => args[0].powf(args[1]),
+        }
+    }
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Result<Expression, Error> {
+        let mut parser = Parser {
+            text,
+            chars: text.char_indices().peekable(),
+        };
+        let root = parser.expression()?;
+        parser.skip_whitespace();
+        match parser.chars.peek() {
+            Some(&(i, c)) => Err(parser.error(i, format!("unexpected `{}`", c))),
+            None => Ok(Expression { root }),
+        }
+    }
+
+    /// Evaluates the expression at the normalized position `(x, y)`.
+    pub fn eval(&self, x: f32, y: f32) -> f32 {
+        eval(&self.root, x, y)
+    }
+}
+
+fn eval(node: &Node, x: f32, y: f32) -> f32 {
+    match node {
+        Node::Number(n) => *n,
+        Node::X => x,
+        Node::Y => y,
+        Node::R => (x - 0.5).hypot(y - 0.5),
+        Node::Negate(node) => -eval(node, x, y),
+        Node::Binary(op, a, b) => {
+            let (a, b) = (eval(a, x, y), eval(b, x, y));
+            match op {
+                '+' => a + b,
+                '-' => a - b,
+                '*' => a * b,
+                '/' => a / b,
+                '%' => a.rem_euclid(b),
+                _ => a.powf(b),
+            }
+        }
+        Node::Call(function, args) => {
+            let args: Vec<f32> = args.iter().map(|arg| eval(arg, x, y)).collect();
+            function.apply(&args)
+        }
+    }
+}
+
+/// Recursive descent parser, binding `^` tighter than unary minus, which binds tighter than
+/// `*`, `/` and `%`, which bind tighter than `+` and `-`.
+struct Parser<'a> {
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn expression(&mut self) -> Result<Node, Error> {
+        let mut node = self.term()?;
+        while let Some(op) = self.operator(&['+', '-']) {
+            node = Node::Binary(op, Box::new(node), Box::new(self.term()?));
+        }
+
+        Ok(node)
+    }
+
+    fn term(&mut self) -> Result<Node, Error> {
+        let mut node = self.unary()?;
+        while let Some(op) = self.operator(&['*', '/', '%']) {
+            node = Node::Binary(op, Box::new(node), Box::new(self.unary()?));
+        }
+
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node, Error> {
+        if self.operator(&['-']).is_some() {
+            return Ok(Node::Negate(Box::new(self.unary()?)));
+        }
+
+        let base = self.atom()?;
+        match self.operator(&['^']) {
+            // Right associative, so `2 ^ 3 ^ 2` is `2 ^ 9`.
+            Some(op) => Ok(Node::Binary(op, Box::new(base), Box::new(self.unary()?))),
+            None => Ok(base),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Node, Error> {
+        self.skip_whitespace();
+        let (start, c) = match self.chars.peek() {
+            Some(&next) => next,
+            None => return Err(self.error(self.text.len(), "expected a value")),
+        };
+
+        if c == '(' {
+            self.chars.next();
+            let node = self.expression()?;
+            self.expect(')')?;
+            Ok(node)
+        } else if c.is_ascii_digit() || c == '.' {
+            let text = self.take_while(|c| c.is_ascii_digit() || c == '.');
+            text.parse::<f32>()
+                .map(Node::Number)
+                .map_err(|_| self.error(start, format!("invalid number `{}`", text)))
+        } else if c.is_ascii_alphabetic() {
+            let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+            self.identifier(start, name)
+        } else {
+            Err(self.error(start, format!("unexpected `{}`", c)))
+        }
+    }
+
+    fn identifier(&mut self, start: usize, name: &str) -> Result<Node, Error> {
+        match name {
+            "x" => return Ok(Node::X),
+            "y" => return Ok(Node::Y),
+            "r" => return Ok(Node::R),
+            "pi" => return Ok(Node::Number(PI)),
+            _ => (),
+        }
+
+        let function = Function::from_name(name)
+            .ok_or_else(|| self.error(start, format!("unknown name `{}`", name)))?;
+        self.expect('(')?;
+        let mut args = vec![self.expression()?];
+        while self.operator(&[',']).is_some() {
+            args.push(self.expression()?);
+        }
+        self.expect(')')?;
+
+        if args.len() != function.arity() {
+            return Err(self.error(
+                start,
+                format!(
+                    "`{}` takes {} arguments, got {}",
+                    name,
+                    function.arity(),
+                    args.len()
+                ),
+            ));
+        }
+
+        Ok(Node::Call(function, args))
+    }
+
+    /// Consumes the next character if it's one of `ops`.
+    fn operator(&mut self, ops: &[char]) -> Option<char> {
+        self.skip_whitespace();
+        let &(_, c) = self.chars.peek()?;
+        if ops.contains(&c) {
+            self.chars.next();
+            Some(c)
+        } else {
+            None
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((i, c)) => Err(self.error(i, format!("expected `{}`, got `{}`", expected, c))),
+            None => Err(self.error(self.text.len(), format!("expected `{}`", expected))),
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let start = self.chars.peek().map_or(self.text.len(), |&(i, _)| i);
+        while self.chars.peek().is_some_and(|&(_, c)| f(c)) {
+            self.chars.next();
+        }
+        let end = self.chars.peek().map_or(self.text.len(), |&(i, _)| i);
+
+        &self.text[start..end]
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|&(_, c)| c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn error(&self, at: usize, message: impl Into<String>) -> Error {
+        Error::Parse {
+            format: "expression",
+            message: format!("{} at {} in `{}`", message.into(), at, self.text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval_at(text: &str, x: f32, y: f32) -> f32 {
+        Expression::parse(text).unwrap().eval(x, y)
+    }
+
+    #[test]
+    fn respects_precedence_and_associativity() {
+        assert_eq!(eval_at("1 + 2 * 3", 0.0, 0.0), 7.0);
+        assert_eq!(eval_at("(1 + 2) * 3", 0.0, 0.0), 9.0);
+        assert_eq!(eval_at("8 - 4 - 2", 0.0, 0.0), 2.0);
+        assert_eq!(eval_at("2 ^ 3 ^ 2", 0.0, 0.0), 512.0);
+        assert_eq!(eval_at("-2 ^ 2", 0.0, 0.0), -4.0);
+        assert_eq!(eval_at("-1 % 3", 0.0, 0.0), 2.0);
+    }
+
+    #[test]
+    fn evaluates_variables_and_functions() {
+        assert_eq!(eval_at("x * 10 + y", 0.5, 0.25), 5.25);
+        assert_eq!(eval_at("r", 0.5, 0.5), 0.0);
+        assert_eq!(eval_at("max(x, y) - min(x, y)", 0.2, 0.7), 0.5);
+        assert_eq!(eval_at("fract(2.75)", 0.0, 0.0), 0.75);
+        assert!((eval_at("sin(pi / 2)", 0.0, 0.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for text in [
+            "", "1 +", "(x", "x)", "foo(x)", "z", "min(x)", "1..2", "x $ y",
+        ] {
+            assert!(
+                Expression::parse(text).is_err(),
+                "`{}` should not parse",
+                text
+            );
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::path::{Path, PathBuf};
+
+use nannou::image::{self, GrayImage, ImageResult};
+use nannou::noise::{Fbm, MultiFractal, NoiseFn, OpenSimplex, Seedable, Value};
+use nannou::prelude::*;
+use nannou::rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure_positive, ensure_unit, Error};
+use crate::expression::Expression;
+
+/// Scalar field over a canvas, yielding values in `[0, 1]`.
+///
+/// Fields are sampled in normalized canvas coordinates, `(0, 0)` being the top left and `(1, 1)`
+/// the bottom right corner, so the same field looks alike on every canvas size.
+pub enum Field {
+    /// Gradient or value noise, `scale` being the noise frequency across the canvas.
+    Noise {
+        noise: Box<dyn NoiseFn<[f64; 2]> + Send>,
+        scale: f32,
+    },
+    /// Distance from `center`, 0 at the center and 1 at `radius` and beyond.
+    Radial { center: Point2, radius: f32 },
+    /// Luminance of an image stretched over the canvas.
+    Image(GrayImage),
+    /// Any function of the normalized position, e.g. a parsed [`Expression`].
+    Custom(Box<dyn Fn(Point2) -> f32 + Send>),
+}
+
+impl Field {
+    pub fn perlin(scale: f32, seed: u32) -> Field {
+        // A single octave fbm is plain perlin noise. `noise` exports two `Perlin` types, which
+        // makes naming either of them directly ambiguous.
+        Field::Noise {
+            noise: Box::new(Fbm::new().set_octaves(1).set_seed(seed)),
+            scale,
+        }
+    }
+
+    pub fn simplex(scale: f32, seed: u32) -> Field {
+        Field::Noise {
+            noise: Box::new(OpenSimplex::new().set_seed(seed)),
+            scale,
+        }
+    }
+
+    pub fn value(scale: f32, seed: u32) -> Field {
+        Field::Noise {
+            noise: Box::new(Value::new().set_seed(seed)),
+            scale,
+        }
+    }
+
+    pub fn radial(center: Point2, radius: f32) -> Field {
+        Field::Radial { center, radius }
+    }
+
+    pub fn image<P: AsRef<Path>>(path: P) -> ImageResult<Field> {
+        Ok(Field::Image(image::open(path)?.to_luma8()))
+    }
+
+    pub fn custom<F>(f: F) -> Field
+    where
+        F: Fn(Point2) -> f32 + Send + 'static,
+    {
+        Field::Custom(Box::new(f))
+    }
+
+    /// Samples the field at the normalized position `uv`.
+    pub fn sample(&self, uv: Point2) -> f32 {
+        let value = match self {
+            Field::Noise { noise, scale } => {
+                let n = noise.get([(uv.x * scale) as f64, (uv.y * scale) as f64]) as f32;
+                map_range(n, -1.0, 1.0, 0.0, 1.0)
+            }
+            Field::Radial { center, radius } => uv.distance(*center) / radius.max(f32::EPSILON),
+            Field::Image(image) if image.width() == 0 || image.height() == 0 => 0.0,
+            Field::Image(image) => {
+                let x = (uv.x.clamp(0.0, 1.0) * (image.width() - 1) as f32).round() as u32;
+                let y = (uv.y.clamp(0.0, 1.0) * (image.height() - 1) as f32).round() as u32;
+                image.get_pixel(x, y).0[0] as f32 / 255.0
+            }
+            Field::Custom(f) => f(uv),
+        };
+
+        value.clamp(0.0, 1.0)
+    }
+
+    /// Samples the field at `point` of the canvas `rect`.
+    pub fn sample_at(&self, point: Point2, rect: Rect) -> f32 {
+        self.sample(pt2(
+            (point.x - rect.left()) / rect.w(),
+            (rect.top() - point.y) / rect.h(),
+        ))
+    }
+}
+
+/// Serializable description of the built in fields, as taken by the sketches.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FieldSource {
+    Perlin {
+        scale: f32,
+        seed: u32,
+    },
+    Simplex {
+        scale: f32,
+        seed: u32,
+    },
+    Value {
+        scale: f32,
+        seed: u32,
+    },
+    Radial {
+        #[serde(with = "crate::serialization::vec2")]
+        center: Point2,
+        radius: f32,
+    },
+    Image {
+        path: PathBuf,
+    },
+    /// An [`Expression`] of the normalized position, e.g. `sin(x * 10) * 0.5 + 0.5`.
+    Expression {
+        expression: String,
+    },
+}
+
+impl FieldSource {
+    /// Creates the field, loading the image of image fields.
+    pub fn build(&self) -> Result<Field, Error> {
+        match self {
+            FieldSource::Perlin { scale, seed } => Ok(Field::perlin(*scale, *seed)),
+            FieldSource::Simplex { scale, seed } => Ok(Field::simplex(*scale, *seed)),
+            FieldSource::Value { scale, seed } => Ok(Field::value(*scale, *seed)),
+            FieldSource::Radial { center, radius } => Ok(Field::radial(*center, *radius)),
+            FieldSource::Image { path } => Field::image(path).map_err(|e| {
+                Error::invalid(
+                    "field image",
+                    format!("failed to load {}: {}", path.display(), e),
+                )
+            }),
+            FieldSource::Expression { expression } => {
+                let expression = Expression::parse(expression)?;
+                Ok(Field::custom(move |uv| expression.eval(uv.x, uv.y)))
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        match self {
+            FieldSource::Perlin { scale, .. }
+            | FieldSource::Simplex { scale, .. }
+            | FieldSource::Value { scale, .. } => ensure_positive("field scale", *scale),
+            FieldSource::Radial { radius, .. } => ensure_positive("field radius", *radius),
+            // Loading is the only way to tell whether the image can be decoded.
+            FieldSource::Image { .. } => self.build().map(drop),
+            FieldSource::Expression { expression } => Expression::parse(expression).map(drop),
+        }
+    }
+}
+
+/// Turns a sampled field value into one out of a number of choices.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldMapping {
+    /// Values below the threshold pick the first choice, all others the last one.
+    Threshold(f32),
+    /// The value is the probability of picking the first choice, otherwise one of the others
+    /// is picked at random.
+    Probability,
+}
+
+impl FieldMapping {
+    pub fn choose<R: Rng>(&self, value: f32, choices: usize, rng: &mut R) -> usize {
+        if choices < 2 {
+            return 0;
+        }
+
+        match *self {
+            FieldMapping::Threshold(threshold) if value < threshold => 0,
+            FieldMapping::Threshold(_) => choices - 1,
+            FieldMapping::Probability if rng.gen::<f32>() < value => 0,
+            FieldMapping::Probability => rng.gen_range(1..choices),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        match *self {
+            FieldMapping::Threshold(threshold) => ensure_unit("threshold", threshold),
+            FieldMapping::Probability => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::Error;
+
+/// File formats scenes, shapes and descriptors can be saved in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Toml,
+    Json,
+    Ron,
+}
+
+impl Format {
+    /// Picks the format by the file extension, `.toml`, `.json` or `.ron`.
+    pub fn from_path(path: &Path) -> Result<Format, Error> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Ok(Format::Toml),
+            Some("json") => Ok(Format::Json),
+            Some("ron") => Ok(Format::Ron),
+            _ => Err(Error::invalid(
+                "file",
+                format!(
+                    "expected a .toml, .json or .ron file, got {}",
+                    path.display()
+                ),
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Toml => "TOML",
+            Format::Json => "JSON",
+            Format::Ron => "RON",
+        }
+    }
+
+    pub fn parse<T: DeserializeOwned>(&self, text: &str) -> Result<T, Error> {
+        match self {
+            Format::Toml => toml::from_str(text).map_err(|e| self.parse_error(e)),
+            Format::Json => serde_json::from_str(text).map_err(|e| self.parse_error(e)),
+            Format::Ron => ron::from_str(text).map_err(|e| self.parse_error(e)),
+        }
+    }
+
+    /// Pretty prints `value`. TOML needs `value` to be a struct or map.
+    pub fn to_string<T: Serialize>(&self, value: &T) -> Result<String, Error> {
+        match self {
+            // Going through a TOML value emits the plain values of tables before nested tables,
+            // which TOML requires but the field order of structs doesn't guarantee.
+            Format::Toml => toml::Value::try_from(value)
+                .and_then(|value| toml::to_string_pretty(&value))
+                .map_err(|e| self.serialize_error(e)),
+            Format::Json => {
+                serde_json::to_string_pretty(value).map_err(|e| self.serialize_error(e))
+            }
+            Format::Ron => ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::new())
+                .map_err(|e| self.serialize_error(e)),
+        }
+    }
+
+    fn parse_error(&self, error: impl ToString) -> Error {
+        Error::Parse {
+            format: self.name(),
+            message: error.to_string(),
+        }
+    }
+
+    fn serialize_error(&self, error: impl ToString) -> Error {
+        Error::Serialize {
+            format: self.name(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Reads `path`, the format being picked by its extension.
+pub fn load<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, Error> {
+    let path = path.as_ref();
+    let format = Format::from_path(path)?;
+    let text = fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    format.parse(&text)
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it into place, so `path`
+/// is never left half written.
+pub fn write_atomically(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<(), Error> {
+    let path = path.as_ref();
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+
+    fs::write(&temporary, contents)
+        .and_then(|_| fs::rename(&temporary, path))
+        .map_err(|source| {
+            // The temporary file is of no use anymore, if it was written at all.
+            let _ = fs::remove_file(&temporary);
+            Error::Io {
+                path: path.to_path_buf(),
+                source,
+            }
+        })
+}
+
+/// Writes `value` to `path`, the format being picked by its extension.
+pub fn save<T: Serialize>(path: impl AsRef<Path>, value: &T) -> Result<(), Error> {
+    let path = path.as_ref();
+    let text = Format::from_path(path)?.to_string(value)?;
+
+    fs::write(path, text).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
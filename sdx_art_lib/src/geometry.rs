@@ -0,0 +1,46 @@
+use nannou::prelude::*;
+
+/// Rotates `point` counterclockwise by `radians` around the origin.
+pub fn rotate(point: Vec2, radians: f32) -> Vec2 {
+    let (sin, cos) = radians.sin_cos();
+
+    vec2(point.x * cos - point.y * sin, point.x * sin + point.y * cos)
+}
+
+/// Rotates `point` counterclockwise by `radians` around `origin`.
+pub fn rotate_around(point: Vec2, origin: Vec2, radians: f32) -> Vec2 {
+    rotate(point - origin, radians) + origin
+}
+
+/// Smallest axis aligned rect containing all `points`, a zero sized rect at the origin if
+/// there are none.
+pub fn bounding_rect(points: &[Point2]) -> Rect {
+    if points.is_empty() {
+        return Rect::from_w_h(0.0, 0.0);
+    }
+
+    let (min, max) = points.iter().fold(
+        (vec2(f32::MAX, f32::MAX), vec2(f32::MIN, f32::MIN)),
+        |(min, max), p| (min.min(*p), max.max(*p)),
+    );
+
+    Rect::from_corners(min, max)
+}
+
+/// Whether `point` lies inside the polygon outlined by `points`, using the even-odd rule.
+pub fn polygon_contains(points: &[Point2], point: Point2) -> bool {
+    let mut inside = false;
+
+    for i in 0..points.len() {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
@@ -0,0 +1,59 @@
+use nannou::prelude::*;
+
+use crate::canvas::Canvas;
+use crate::geometry::rotate;
+use crate::stroke::StrokeStyle;
+
+/// A bundle of parallel lines of equal length, spread evenly around a center.
+///
+/// Unrotated the lines are vertical and lined up side by side along the x axis.
+#[derive(Clone, Copy, Debug)]
+pub struct LineGlyph {
+    pub count: usize,
+    pub length: f32,
+    /// Distance between two neighbouring lines.
+    pub spacing: f32,
+    /// Counterclockwise rotation in radians.
+    pub rotation: f32,
+}
+
+impl LineGlyph {
+    pub fn new(count: usize, length: f32) -> LineGlyph {
+        LineGlyph {
+            count,
+            length,
+            spacing: length / count.max(1) as f32,
+            rotation: 0.0,
+        }
+    }
+
+    pub fn with_spacing(&self, spacing: f32) -> LineGlyph {
+        LineGlyph { spacing, ..*self }
+    }
+
+    pub fn with_rotation(&self, rotation: f32) -> LineGlyph {
+        LineGlyph { rotation, ..*self }
+    }
+
+    /// Start and end points of the lines when the glyph is centered on `center`.
+    pub fn lines(&self, center: Point2) -> Vec<(Point2, Point2)> {
+        let half_length = self.length / 2.0;
+        let middle = (self.count as f32 - 1.0) / 2.0;
+
+        (0..self.count)
+            .map(|i| {
+                let x = (i as f32 - middle) * self.spacing;
+                let start = rotate(vec2(x, -half_length), self.rotation);
+                let end = rotate(vec2(x, half_length), self.rotation);
+
+                (center + start, center + end)
+            })
+            .collect()
+    }
+
+    pub fn draw(&self, canvas: &dyn Canvas, center: Point2, style: &StrokeStyle) {
+        for (start, end) in self.lines(center) {
+            canvas.stroke_line(start, end, style);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use nannou::prelude::*;
+use nannou::rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure_at_least, ensure_positive, ensure_unit, Error};
+use crate::subdivision::quadtree;
+
+/// Cell layout used to tile a canvas.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Grid {
+    /// `columns` x `rows` rectangles stretched over the whole canvas.
+    Rectangular { columns: usize, rows: usize },
+    /// Pointy topped hexagons with the given circumradius, every other row shifted by half a cell.
+    Hexagonal { radius: f32 },
+    /// Alternating up and down pointing equilateral triangles with the given side length.
+    Triangular { side: f32 },
+    /// Rectangular grid whose cells are split into quarters with `split_probability`,
+    /// recursively up to `max_depth` times.
+    Subdivided {
+        columns: usize,
+        rows: usize,
+        max_depth: usize,
+        split_probability: f32,
+    },
+}
+
+/// A single grid cell, described by its outline in clockwise order.
+#[derive(Clone, Debug)]
+pub struct Cell {
+    pub center: Point2,
+    pub vertices: Vec<Point2>,
+    pub depth: usize,
+}
+
+impl Cell {
+    pub fn new(vertices: Vec<Point2>, depth: usize) -> Cell {
+        let center =
+            vertices.iter().fold(vec2(0.0, 0.0), |sum, v| sum + *v) / vertices.len().max(1) as f32;
+
+        Cell {
+            center,
+            vertices,
+            depth,
+        }
+    }
+
+    pub fn from_rect(rect: Rect, depth: usize) -> Cell {
+        Cell::new(
+            vec![
+                rect.top_left(),
+                rect.top_right(),
+                rect.bottom_right(),
+                rect.bottom_left(),
+            ],
+            depth,
+        )
+    }
+
+    /// Number of distinct diagonals [`Cell::diagonal`] can return.
+    pub fn diagonal_count(&self) -> usize {
+        let len = self.vertices.len();
+
+        if len.is_multiple_of(2) {
+            len / 2
+        } else {
+            len
+        }
+    }
+
+    /// Returns the `i`th diagonal of the cell. Cells with an odd number of vertices have no
+    /// opposite vertex, so the diagonal runs from a vertex to the middle of the opposite edge.
+    pub fn diagonal(&self, i: usize) -> (Point2, Point2) {
+        let vertices = &self.vertices;
+        let len = vertices.len();
+        let i = i % self.diagonal_count().max(1);
+
+        if len.is_multiple_of(2) {
+            (vertices[i], vertices[i + len / 2])
+        } else {
+            let a = vertices[(i + len / 2) % len];
+            let b = vertices[(i + len / 2 + 1) % len];
+
+            (vertices[i], (a + b) / 2.0)
+        }
+    }
+}
+
+impl Grid {
+    /// Lays out the cells covering `rect`. Cells at the border may reach beyond it.
+    ///
+    /// `rng` is only consulted by [`Grid::Subdivided`].
+    pub fn cells<R: Rng>(&self, rect: Rect, rng: &mut R) -> Vec<Cell> {
+        match *self {
+            Grid::Rectangular { columns, rows } => rectangular_cells(rect, columns, rows)
+                .into_iter()
+                .map(|r| Cell::from_rect(r, 0))
+                .collect(),
+            Grid::Hexagonal { radius } => hexagonal_cells(rect, radius),
+            Grid::Triangular { side } => triangular_cells(rect, side),
+            Grid::Subdivided {
+                columns,
+                rows,
+                max_depth,
+                split_probability,
+            } => rectangular_cells(rect, columns, rows)
+                .into_iter()
+                .flat_map(|r| {
+                    quadtree(r, max_depth, &mut |_, _| {
+                        rng.gen::<f32>() < split_probability
+                    })
+                })
+                .map(|(r, depth)| Cell::from_rect(r, depth))
+                .collect(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        match *self {
+            Grid::Rectangular { columns, rows } => {
+                ensure_at_least("columns", columns, 1)?;
+                ensure_at_least("rows", rows, 1)
+            }
+            Grid::Hexagonal { radius } => ensure_positive("hexagon radius", radius),
+            Grid::Triangular { side } => ensure_positive("triangle side", side),
+            Grid::Subdivided {
+                columns,
+                rows,
+                split_probability,
+                ..
+            } => {
+                ensure_at_least("columns", columns, 1)?;
+                ensure_at_least("rows", rows, 1)?;
+                ensure_unit("split probability", split_probability)
+            }
+        }
+    }
+}
+
+/// Splits `rect` into `columns` x `rows` equally sized rects, row by row from the top left.
+pub fn rectangular_cells(rect: Rect, columns: usize, rows: usize) -> Vec<Rect> {
+    let columns = columns.max(1);
+    let rows = rows.max(1);
+    let w = rect.w() / columns as f32;
+    let h = rect.h() / rows as f32;
+
+    (0..rows)
+        .flat_map(|row| {
+            (0..columns).map(move |column| {
+                Rect::from_x_y_w_h(
+                    rect.left() + w * (column as f32 + 0.5),
+                    rect.top() - h * (row as f32 + 0.5),
+                    w,
+                    h,
+                )
+            })
+        })
+        .collect()
+}
+
+fn hexagonal_cells(rect: Rect, radius: f32) -> Vec<Cell> {
+    let radius = radius.max(1.0);
+    let w = 3f32.sqrt() * radius;
+    let h = 1.5 * radius;
+
+    let mut cells = vec![];
+    let mut row = 0;
+    let mut y = rect.top();
+    while y + radius > rect.bottom() {
+        let mut x = rect.left() + if row % 2 == 1 { w / 2.0 } else { 0.0 };
+
+        while x - w / 2.0 < rect.right() {
+            let vertices = (0..6)
+                .map(|i| {
+                    let radian = deg_to_rad(90.0 - 60.0 * i as f32);
+                    pt2(x + radian.cos() * radius, y + radian.sin() * radius)
+                })
+                .collect();
+            cells.push(Cell::new(vertices, 0));
+            x += w;
+        }
+
+        y -= h;
+        row += 1;
+    }
+
+    cells
+}
+
+fn triangular_cells(rect: Rect, side: f32) -> Vec<Cell> {
+    let side = side.max(1.0);
+    let h = 3f32.sqrt() / 2.0 * side;
+
+    let mut cells = vec![];
+    let mut row = 0;
+    let mut top = rect.top();
+    while top > rect.bottom() {
+        let bottom = top - h;
+
+        let mut i = -1;
+        let mut x = rect.left() - side / 2.0;
+        while x < rect.right() {
+            let vertices = if (i + row) % 2 == 0 {
+                vec![
+                    pt2(x + side / 2.0, top),
+                    pt2(x + side, bottom),
+                    pt2(x, bottom),
+                ]
+            } else {
+                vec![pt2(x, top), pt2(x + side, top), pt2(x + side / 2.0, bottom)]
+            };
+            cells.push(Cell::new(vertices, 0));
+
+            x += side / 2.0;
+            i += 1;
+        }
+
+        top = bottom;
+        row += 1;
+    }
+
+    cells
+}
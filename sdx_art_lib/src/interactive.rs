@@ -0,0 +1,283 @@
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use nannou::prelude::*;
+
+use crate::canvas::Canvas;
+use crate::error::Error;
+use crate::metadata::Metadata;
+use crate::sketch;
+
+/// Keys decreasing and increasing the first, second and third parameter of a sketch.
+const BUMP_KEYS: [(Key, Key, &str); 3] = [
+    (Key::Down, Key::Up, "Down/Up"),
+    (Key::Left, Key::Right, "Left/Right"),
+    (Key::LBracket, Key::RBracket, "[/]"),
+];
+const OVERLAY_WIDTH: f32 = 320.0;
+const OVERLAY_LINE: f32 = 22.0;
+const OVERLAY_TEXT: f32 = 14.0;
+
+/// What a key press or mouse click asks an interactive preview to do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Regenerate with a new random seed.
+    Reseed,
+    /// Decrease (-1) or increase (1) the parameter with the index.
+    Bump(usize, i32),
+    ToggleOverlay,
+    /// Save the current frame along with its parameters.
+    Save,
+}
+
+impl Action {
+    pub fn from_key(key: Key) -> Option<Action> {
+        match key {
+            Key::R => Some(Action::Reseed),
+            Key::H => Some(Action::ToggleOverlay),
+            Key::S => Some(Action::Save),
+            _ => BUMP_KEYS
+                .iter()
+                .enumerate()
+                .find_map(|(i, (down, up, _))| match key {
+                    k if k == *down => Some(Action::Bump(i, -1)),
+                    k if k == *up => Some(Action::Bump(i, 1)),
+                    _ => None,
+                }),
+        }
+    }
+
+    pub fn from_mouse(button: MouseButton) -> Option<Action> {
+        match button {
+            MouseButton::Left => Some(Action::Reseed),
+            _ => None,
+        }
+    }
+}
+
+/// A parameter shown on the overlay.
+///
+/// The first three adjustable parameters are bumped by the bump keys, in the order they're
+/// listed in.
+pub struct Tweak {
+    pub name: &'static str,
+    pub value: String,
+    pub adjustable: bool,
+}
+
+impl Tweak {
+    pub fn new(name: &'static str, value: impl ToString) -> Tweak {
+        Tweak {
+            name,
+            value: value.to_string(),
+            adjustable: true,
+        }
+    }
+
+    /// A parameter that's only shown, e.g. the seed.
+    pub fn fixed(name: &'static str, value: impl ToString) -> Tweak {
+        Tweak {
+            adjustable: false,
+            ..Tweak::new(name, value)
+        }
+    }
+}
+
+/// Title of interactive preview windows, pointing out the overlay.
+pub fn title(sketch: &str) -> String {
+    format!("{} - press H for the controls", sketch)
+}
+
+/// Draws the parameters and the keys adjusting them onto a dark panel at the top left of `rect`.
+pub fn draw_overlay(canvas: &dyn Canvas, rect: Rect, tweaks: &[Tweak]) {
+    let mut bump_keys = BUMP_KEYS.iter();
+    let mut lines: Vec<String> = tweaks
+        .iter()
+        .map(|tweak| {
+            let keys = if tweak.adjustable {
+                bump_keys.next()
+            } else {
+                None
+            };
+            match keys {
+                Some((_, _, keys)) => format!("{}: {}   {}", tweak.name, tweak.value, keys),
+                None => format!("{}: {}", tweak.name, tweak.value),
+            }
+        })
+        .collect();
+    lines.push(String::from("R/click: reseed   S: save   H: hide"));
+
+    let bottom = rect.top() - OVERLAY_LINE * (lines.len() as f32 + 1.0);
+    let right = rect.left() + OVERLAY_WIDTH;
+    canvas.fill_polygon(
+        &[
+            rect.top_left(),
+            pt2(right, rect.top()),
+            pt2(right, bottom),
+            pt2(rect.left(), bottom),
+        ],
+        Rgba::new(0.0, 0.0, 0.0, 0.7),
+    );
+
+    for (i, line) in lines.iter().enumerate() {
+        let top = rect.top() - OVERLAY_LINE * (i as f32 + 0.5);
+        canvas.fill_text(
+            line,
+            Rect::from_corners(pt2(rect.left(), top), pt2(right, top - OVERLAY_LINE)),
+            OVERLAY_TEXT,
+            Rgba::new(1.0, 1.0, 1.0, 1.0),
+        );
+    }
+}
+
+/// A sketch previewed interactively, implemented by its model so the handlers below can be
+/// registered with the window and app.
+pub trait Interactive {
+    /// Regenerates the sketch with a new random seed.
+    fn reseed(&mut self, app: &App);
+
+    /// Decreases (-1) or increases (1) the parameter with the index, see [`Tweak`].
+    fn bump(&mut self, parameter: usize, direction: i32);
+
+    /// Parameters shown on the overlay.
+    fn tweaks(&self) -> Vec<Tweak>;
+
+    /// Describes the frame shown, saved along with it.
+    fn metadata(&self, app: &App) -> Metadata;
+
+    fn controls(&self) -> &Controls;
+}
+
+/// What the handlers keep track of in the model of an interactive sketch.
+#[derive(Default)]
+pub struct Controls {
+    overlay: Cell<bool>,
+    captures: Captures,
+}
+
+/// Acts on the key pressed, meant to be registered with the window.
+pub fn key_pressed<M: Interactive>(app: &App, model: &mut M, key: Key) {
+    if let Some(action) = Action::from_key(key) {
+        act(app, model, action);
+    }
+}
+
+/// Acts on the mouse button pressed, meant to be registered with the window.
+pub fn mouse_pressed<M: Interactive>(app: &App, model: &mut M, button: MouseButton) {
+    if let Some(action) = Action::from_mouse(button) {
+        act(app, model, action);
+    }
+}
+
+pub fn act<M: Interactive>(app: &App, model: &mut M, action: Action) {
+    match action {
+        Action::Reseed => model.reseed(app),
+        Action::Bump(parameter, direction) => model.bump(parameter, direction),
+        Action::ToggleOverlay => {
+            let overlay = &model.controls().overlay;
+            overlay.set(!overlay.get());
+        }
+        Action::Save => model.controls().captures.request(model.metadata(app)),
+    }
+}
+
+/// Draws the overlay if it's shown, unless the frame is being saved.
+pub fn draw_controls<M: Interactive>(app: &App, canvas: &dyn Canvas, rect: Rect, model: &M) {
+    let controls = model.controls();
+    if !controls.captures.capture(app) && controls.overlay.get() {
+        draw_overlay(canvas, rect, &model.tweaks());
+    }
+}
+
+/// Finishes saving the frames captured since the last update, reporting what's been saved.
+/// Meant to be called on every update of the app.
+pub fn update<M: Interactive>(app: &App, model: &M) {
+    match model.controls().captures.finish(app) {
+        Ok(paths) => paths
+            .iter()
+            .for_each(|path| sketch::report(format_args!("Saved {}", path.display()))),
+        Err(e) => sketch::report(format_args!("Failed to save the frame: {}", e)),
+    }
+}
+
+/// Finishes saving the frames captured last, meant to be registered with the app.
+pub fn exit<M: Interactive>(app: &App, model: M) {
+    update(app, &model);
+}
+
+/// Frames saved from an interactive preview.
+///
+/// Frames are captured while drawing them and written by nannou in the background, so their
+/// metadata is only embedded, and their sidecar only written, once they're finished.
+#[derive(Default)]
+pub struct Captures {
+    requested: RefCell<Option<(PathBuf, Metadata)>>,
+    captured: RefCell<Vec<(PathBuf, Metadata)>>,
+}
+
+impl Captures {
+    /// Has the next frame captured, as `<sketch>-<milliseconds since the epoch>.png` in the
+    /// working directory.
+    pub fn request(&self, metadata: Metadata) {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let path = PathBuf::from(format!("{}-{}.png", metadata.sketch, millis));
+
+        *self.requested.borrow_mut() = Some((path, metadata));
+    }
+
+    /// Captures the frame being drawn if requested. Returns whether it does, so overlays can be
+    /// left out of the capture.
+    pub fn capture(&self, app: &App) -> bool {
+        match self.requested.borrow_mut().take() {
+            Some((path, metadata)) => {
+                app.main_window().capture_frame(&path);
+                self.captured.borrow_mut().push((path, metadata));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Waits for the frames captured so far to be written, then embeds their metadata and saves
+    /// their sidecars. Returns the paths of the saved frames.
+    pub fn finish(&self, app: &App) -> Result<Vec<PathBuf>, Error> {
+        let captured: Vec<_> = self.captured.borrow_mut().drain(..).collect();
+        if captured.is_empty() {
+            return Ok(vec![]);
+        }
+
+        app.main_window()
+            .await_capture_frame_jobs()
+            .map_err(|_| Error::Serialize {
+                format: "PNG",
+                message: String::from("timed out waiting for the captured frames"),
+            })?;
+        captured
+            .into_iter()
+            .map(|(path, metadata)| {
+                metadata.embed_in_png(&path)?;
+                metadata.save_sidecar(&path)?;
+                Ok(path)
+            })
+            .collect()
+    }
+}
+
+/// Bumps a count by `delta`, keeping it within `min..=max`.
+pub fn bump_count(value: usize, delta: i32, min: usize, max: usize) -> usize {
+    let bumped = if delta < 0 {
+        value.saturating_sub(delta.unsigned_abs() as usize)
+    } else {
+        value.saturating_add(delta as usize)
+    };
+
+    bumped.clamp(min, max.max(min))
+}
+
+/// Bumps a value by `delta`, keeping it within `min..=max`.
+pub fn bump_value(value: f32, delta: f32, min: f32, max: f32) -> f32 {
+    (value + delta).clamp(min, max.max(min))
+}
@@ -0,0 +1,63 @@
+use nannou::prelude::*;
+use nannou::rand::Rng;
+
+/// Rows of a triangular lattice covering `rect` from top to bottom, every other row being
+/// shifted by half the `spacing`. Each point is moved by up to `jitter` along both axes.
+///
+/// The rows reach a bit beyond `rect` so the mesh built from them covers it completely.
+pub fn triangular_lattice<R: Rng>(
+    rect: Rect,
+    spacing: f32,
+    jitter: f32,
+    rng: &mut R,
+) -> Vec<Vec<Point2>> {
+    let spacing = spacing.max(1.0);
+    let jitter = jitter.abs();
+    let row_height = 3f32.sqrt() / 2.0 * spacing;
+
+    let rows = (rect.h() / row_height).ceil() as usize + 2;
+    let columns = (rect.w() / spacing).ceil() as usize + 3;
+
+    (0..rows)
+        .map(|row| {
+            let shift = if row % 2 == 1 { spacing / 2.0 } else { 0.0 };
+            let y = rect.top() + row_height - row as f32 * row_height;
+
+            (0..columns)
+                .map(|column| {
+                    let x = rect.left() - spacing + shift + column as f32 * spacing;
+
+                    pt2(
+                        x + rng.gen_range(-jitter..=jitter),
+                        y + rng.gen_range(-jitter..=jitter),
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Triangles between each pair of neighbouring rows of a [`triangular_lattice`].
+pub fn lattice_triangles(rows: &[Vec<Point2>]) -> Vec<[Point2; 3]> {
+    let mut triangles = vec![];
+
+    for (i, pair) in rows.windows(2).enumerate() {
+        let (unshifted, shifted) = if i % 2 == 0 {
+            (&pair[0], &pair[1])
+        } else {
+            (&pair[1], &pair[0])
+        };
+
+        let zipped = unshifted
+            .iter()
+            .zip(shifted.iter())
+            .flat_map(|(a, b)| [*a, *b])
+            .collect::<Vec<Point2>>();
+
+        for window in zipped.windows(3) {
+            triangles.push([window[0], window[1], window[2]]);
+        }
+    }
+
+    triangles
+}
@@ -1,17 +1,27 @@
-use nannou::prelude::*;
-
+pub mod canvas;
+pub mod chords;
+pub mod color;
+pub mod contact_sheet;
+pub mod curves;
+pub mod error;
+pub mod expression;
+pub mod field;
+pub mod format;
+pub mod geometry;
+pub mod glyph;
+pub mod grid;
+pub mod interactive;
+pub mod lattice;
+pub mod metadata;
+pub mod packing;
+pub mod palette;
+pub mod scene;
+pub mod serialization;
 pub mod shapes;
+pub mod sketch;
+pub mod sketchy;
+pub mod stroke;
+pub mod subdivision;
+pub mod watch;
 
-pub fn points_list_diagonals(points: &[Point2]) -> Vec<(Point2, Point2)> {
-    let mut points = points.to_owned();
-
-    let len = points.len();
-    if len % 2 != 0 {
-        points.pop().unwrap();
-    }
-    let half_len = len / 2;
-
-    (0..half_len)
-        .map(|i| (*points.get(i).unwrap(), *points.get(i + half_len).unwrap()))
-        .collect()
-}
+pub use error::Error;
@@ -0,0 +1,312 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::format;
+
+/// Keyword of the PNG text chunk holding the metadata.
+const PNG_KEYWORD: &str = "sdx_art";
+const PNG_SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Everything needed to render an exported piece again, stored in a JSON sidecar next to the
+/// export and embedded into the exported SVG or PNG itself.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Metadata {
+    /// Name of the sketch, e.g. `"mondrian"`.
+    pub sketch: String,
+    /// All descriptor parameters of the sketch.
+    pub parameters: serde_json::Value,
+    pub seed: u64,
+    /// Version of the crate the piece was rendered with.
+    pub version: String,
+    pub width: u32,
+    pub height: u32,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl Metadata {
+    /// Describes a render happening now.
+    pub fn new(
+        sketch: &str,
+        parameters: serde_json::Value,
+        seed: u64,
+        version: &str,
+        (width, height): (u32, u32),
+    ) -> Metadata {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        Metadata {
+            sketch: sketch.to_string(),
+            parameters,
+            seed,
+            version: version.to_string(),
+            width,
+            height,
+            timestamp,
+        }
+    }
+
+    /// Compact JSON with all non ASCII characters escaped, so it fits into a PNG text chunk.
+    pub fn to_json(&self) -> String {
+        let json = serde_json::to_string(self).expect("metadata is always serializable");
+
+        let mut ascii = String::with_capacity(json.len());
+        for c in json.chars() {
+            if c.is_ascii() {
+                ascii.push(c);
+            } else {
+                // Non ASCII characters only occur within JSON strings, where escapes are valid.
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    ascii.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+        }
+
+        ascii
+    }
+
+    pub fn from_json(json: &str) -> Result<Metadata, Error> {
+        serde_json::from_str(json).map_err(|e| Error::Parse {
+            format: "JSON",
+            message: e.to_string(),
+        })
+    }
+
+    /// Path of the sidecar of the export at `path`, e.g. `piece.json` for `piece.png`.
+    pub fn sidecar_path(path: &Path) -> PathBuf {
+        path.with_extension("json")
+    }
+
+    pub fn save_sidecar(&self, path: &Path) -> Result<(), Error> {
+        let sidecar = Metadata::sidecar_path(path);
+        let json = serde_json::to_string_pretty(self).expect("metadata is always serializable");
+
+        fs::write(&sidecar, json).map_err(|source| Error::Io {
+            path: sidecar,
+            source,
+        })
+    }
+
+    /// Reads the metadata of an export, or of a sidecar if given a `.json` file.
+    pub fn read(path: &Path) -> Result<Metadata, Error> {
+        let bytes = fs::read(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let missing = || {
+            Error::invalid(
+                "export",
+                format!("{} holds no sdx_art metadata", path.display()),
+            )
+        };
+
+        let json = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => String::from_utf8_lossy(&bytes).into_owned(),
+            Some("svg") => svg_metadata(&String::from_utf8_lossy(&bytes)).ok_or_else(missing)?,
+            Some("png") => png_text(&bytes, PNG_KEYWORD).ok_or_else(missing)?,
+            _ => {
+                return Err(Error::invalid(
+                    "export",
+                    format!(
+                        "expected a .png, .svg or .json file, got {}",
+                        path.display()
+                    ),
+                ))
+            }
+        };
+
+        Metadata::from_json(&json)
+    }
+
+    /// Writes `png` to `path` with the metadata added as a text chunk. The file is replaced
+    /// atomically, so it's never left truncated.
+    pub fn save_png(&self, path: &Path, png: &[u8]) -> Result<(), Error> {
+        let png =
+            insert_png_text(png, PNG_KEYWORD, &self.to_json()).ok_or_else(|| Error::Serialize {
+                format: "PNG",
+                message: format!("the image for {} is not a well formed PNG", path.display()),
+            })?;
+
+        format::write_atomically(path, png)
+    }
+
+    /// Adds the metadata as a text chunk to the PNG at `path`, e.g. to a frame written by nannou.
+    pub fn embed_in_png(&self, path: &Path) -> Result<(), Error> {
+        let png = fs::read(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        self.save_png(path, &png)
+    }
+}
+
+/// Unescaped content of the first `<metadata>` element of an SVG document.
+pub fn svg_metadata(svg: &str) -> Option<String> {
+    let start = svg.find("<metadata>")? + "<metadata>".len();
+    let end = start + svg[start..].find("</metadata>")?;
+
+    Some(
+        svg[start..end]
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// Text of the first PNG `tEXt` chunk with the given keyword.
+pub(crate) fn png_text(png: &[u8], keyword: &str) -> Option<String> {
+    png_chunks(png)?.into_iter().find_map(|(kind, data)| {
+        let (key, text) = data.split_at(data.iter().position(|b| *b == 0)?);
+
+        (&kind == b"tEXt" && key == keyword.as_bytes())
+            .then(|| text[1..].iter().map(|b| *b as char).collect())
+    })
+}
+
+/// Inserts a `tEXt` chunk right before the closing `IEND` chunk. `text` is expected to be
+/// Latin-1, which ASCII is a subset of.
+pub(crate) fn insert_png_text(png: &[u8], keyword: &str, text: &str) -> Option<Vec<u8>> {
+    let chunks = png_chunks(png)?;
+    let end = png.len() - chunks.last().filter(|(kind, _)| kind == b"IEND")?.1.len() - 12;
+
+    let mut data = keyword.as_bytes().to_vec();
+    data.push(0);
+    data.extend(text.bytes());
+
+    let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+    chunk.extend(b"tEXt");
+    chunk.extend(&data);
+    chunk.extend(crc32(&chunk[4..]).to_be_bytes());
+
+    Some([&png[..end], &chunk[..], &png[end..]].concat())
+}
+
+/// Types and data of all chunks, `None` if `png` isn't a well formed PNG file.
+fn png_chunks(png: &[u8]) -> Option<Vec<([u8; 4], &[u8])>> {
+    let mut rest = png.strip_prefix(PNG_SIGNATURE)?;
+    let mut chunks = vec![];
+
+    while !rest.is_empty() {
+        let length = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+        let kind: [u8; 4] = rest.get(4..8)?.try_into().ok()?;
+        let data = rest.get(8..8 + length)?;
+        chunks.push((kind, data));
+
+        rest = rest.get(12 + length..)?;
+    }
+
+    Some(chunks)
+}
+
+/// CRC-32 as used by PNG chunks.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+        chunk.extend(kind);
+        chunk.extend(data);
+        chunk.extend(crc32(&chunk[4..]).to_be_bytes());
+        chunk
+    }
+
+    fn png() -> Vec<u8> {
+        let header = [0, 0, 0, 1, 0, 0, 0, 1, 8, 6, 0, 0, 0];
+        [
+            PNG_SIGNATURE,
+            &chunk(b"IHDR", &header),
+            &chunk(b"IEND", &[]),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn crc32_matches_png_chunks() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn inserts_text_before_the_end() {
+        let original = png();
+        let png = insert_png_text(&original, "key", "{\"seed\":1}").unwrap();
+
+        let kinds: Vec<[u8; 4]> = png_chunks(&png).unwrap().iter().map(|c| c.0).collect();
+        assert_eq!(kinds, [*b"IHDR", *b"tEXt", *b"IEND"]);
+        // The chunk is complete with its CRC, and everything else is left as it was.
+        let end = original.len() - 12;
+        assert_eq!(
+            png,
+            [
+                &original[..end],
+                &chunk(b"tEXt", b"key\0{\"seed\":1}"),
+                &original[end..],
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn reads_text_by_keyword() {
+        let png = insert_png_text(&png(), "other", "ignored").unwrap();
+        let png = insert_png_text(&png, "key", "value").unwrap();
+
+        assert_eq!(png_text(&png, "key").as_deref(), Some("value"));
+        assert_eq!(png_text(&png, "other").as_deref(), Some("ignored"));
+        assert_eq!(png_text(&png, "missing"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_pngs() {
+        let png = png();
+
+        assert_eq!(insert_png_text(&png[8..], "key", "value"), None);
+        assert_eq!(insert_png_text(&png[..png.len() - 1], "key", "value"), None);
+        assert_eq!(
+            insert_png_text(&png[..png.len() - 12], "key", "value"),
+            None
+        );
+        assert_eq!(png_text(&png[..png.len() - 1], "key"), None);
+    }
+
+    #[test]
+    fn saves_pngs_with_metadata() {
+        let path =
+            std::env::temp_dir().join(format!("sdx_art_metadata_{}.png", std::process::id()));
+        let metadata = Metadata::new("test", serde_json::json!({"size": 3}), 1, "0.0.0", (1, 1));
+
+        metadata.save_png(&path, &png()).unwrap();
+        let read = Metadata::read(&path);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(read.unwrap().to_json(), metadata.to_json());
+        assert!(!path.with_extension("png.tmp").exists());
+        assert!(metadata.save_png(&path, &png()[8..]).is_err());
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,122 @@
+use nannou::prelude::*;
+use std::path::Path;
+
+use nannou::rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::format;
+use crate::shapes::circle::Circle;
+
+/// Circles of a finished packing, so expensive packings can be saved once and re-rendered in
+/// different styles.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Packing {
+    pub circles: Vec<Circle>,
+}
+
+impl Packing {
+    /// Reads a packing saved with [`Packing::save`], the format being picked by the extension.
+    pub fn load(path: impl AsRef<Path>) -> Result<Packing, Error> {
+        format::load(path)
+    }
+
+    /// Writes the packing as TOML, JSON or RON, depending on the extension of `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        format::save(path, self)
+    }
+}
+
+/// Packs up to `total_circles` non overlapping circles into `rect`.
+///
+/// Each circle is dropped at a random free spot, taking up to `attempts` tries, and then grown
+/// from `min_radius` until it touches another circle, the border of `rect` or `max_radius`.
+pub fn pack_circles<R: Rng>(
+    rect: &Rect,
+    min_radius: usize,
+    max_radius: usize,
+    total_circles: usize,
+    attempts: usize,
+    rng: &mut R,
+) -> Vec<Circle> {
+    let mut circles = vec![];
+
+    for _ in 0..total_circles {
+        if let Some(circle) =
+            try_create_circle(rect, &circles, min_radius, max_radius, attempts, rng)
+        {
+            circles.push(circle);
+        }
+    }
+
+    circles
+}
+
+fn try_position_circle<R: Rng>(
+    rect: &Rect,
+    circles: &[Circle],
+    min_radius: usize,
+    attempts: usize,
+    rng: &mut R,
+) -> Option<Circle> {
+    for _ in 0..attempts {
+        let circle = Circle::new(min_radius as f32).with_x_y(
+            rng.gen_range(rect.left()..rect.right()),
+            rng.gen_range(rect.bottom()..rect.top()),
+        );
+
+        if !circle_has_collision(&circle, circles, rect) {
+            return Some(circle);
+        }
+    }
+
+    None
+}
+
+fn try_create_circle<R: Rng>(
+    rect: &Rect,
+    circles: &[Circle],
+    min_radius: usize,
+    max_radius: usize,
+    attempts: usize,
+    rng: &mut R,
+) -> Option<Circle> {
+    let mut circle = try_position_circle(rect, circles, min_radius, attempts, rng)?;
+
+    for radius in min_radius..max_radius {
+        circle = circle.with_radius((radius) as f32);
+        if circle_has_collision(&circle, circles, rect) {
+            circle = circle.with_radius((radius - 1) as f32);
+
+            break;
+        }
+    }
+
+    Some(circle)
+}
+
+fn circle_has_collision(circle: &Circle, circles: &[Circle], rect: &Rect) -> bool {
+    for other_circle in circles.iter() {
+        let a = circle.radius + other_circle.radius;
+        let x = circle.pos.x - other_circle.pos.x;
+        let y = circle.pos.y - other_circle.pos.y;
+
+        if a >= ((x * x) + (y * y)).sqrt() {
+            return true;
+        }
+    }
+
+    if circle.pos.x + circle.radius >= rect.top_right().x
+        || circle.pos.x - circle.radius <= rect.top_left().x
+    {
+        return true;
+    }
+
+    if circle.pos.y + circle.radius >= rect.top_left().y
+        || circle.pos.y - circle.radius <= rect.bottom_left().y
+    {
+        return true;
+    }
+
+    false
+}
@@ -0,0 +1,62 @@
+use nannou::prelude::*;
+use nannou::rand::seq::SliceRandom;
+use nannou::rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Color sets to pick fills from.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Palette {
+    /// Any shade between black and white.
+    Grayscale,
+    /// Red, blue and yellow as used by Piet Mondrian.
+    Primary,
+    /// Soft pastel tones.
+    Pastel,
+    /// Dark to light greens.
+    Greens,
+}
+
+impl Palette {
+    /// Looks up a palette by its lowercase name, e.g. `"pastel"`.
+    pub fn from_name(name: &str) -> Option<Palette> {
+        match name {
+            "grayscale" => Some(Palette::Grayscale),
+            "primary" => Some(Palette::Primary),
+            "pastel" => Some(Palette::Pastel),
+            "greens" => Some(Palette::Greens),
+            _ => None,
+        }
+    }
+
+    /// The discrete colors of the palette, empty for continuous palettes like grayscale.
+    pub fn colors(&self) -> Vec<Rgba> {
+        let hex: &[u32] = match self {
+            Palette::Grayscale => &[],
+            Palette::Primary => &[0xd40920, 0x1356a2, 0xf7d842],
+            Palette::Pastel => &[0xffadad, 0xffd6a5, 0xfdffb6, 0xcaffbf, 0x9bf6ff, 0xa0c4ff],
+            Palette::Greens => &[0x081c15, 0x1b4332, 0x2d6a4f, 0x40916c, 0x74c69d, 0xb7e4c7],
+        };
+
+        hex.iter()
+            .map(|c| {
+                Rgba::new(
+                    ((c >> 16) & 0xff) as f32 / 255.0,
+                    ((c >> 8) & 0xff) as f32 / 255.0,
+                    (c & 0xff) as f32 / 255.0,
+                    1.0,
+                )
+            })
+            .collect()
+    }
+
+    pub fn pick<R: Rng>(&self, rng: &mut R) -> Rgba {
+        match self {
+            Palette::Grayscale => {
+                let gray = rng.gen::<f32>();
+                Rgba::new(gray, gray, gray, 1.0)
+            }
+            _ => *self.colors().choose(rng).unwrap(),
+        }
+    }
+}
@@ -0,0 +1,630 @@
+use std::collections::BTreeMap;
+use std::f32::consts::TAU;
+use std::path::Path;
+
+use nannou::prelude::*;
+use nannou::rand::rngs::StdRng;
+use nannou::rand::seq::SliceRandom;
+use nannou::rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::canvas::Canvas;
+use crate::chords::ChordPattern;
+use crate::color;
+use crate::error::{ensure_at_least, ensure_non_negative, ensure_positive, Error};
+use crate::format::{self, Format};
+use crate::grid::Grid;
+use crate::packing::pack_circles;
+use crate::palette::Palette;
+use crate::shapes::circle::Circle;
+use crate::shapes::circular::{Shape, Spacing};
+use crate::shapes::polygon::Polygon;
+use crate::shapes::Drawable;
+use crate::stroke::StrokeStyle;
+
+/// Declarative description of a piece: the canvas, named palettes and the elements drawn on it,
+/// in drawing order.
+///
+/// Colors are given as `#rrggbb` or `#rrggbbaa` hex values, as the name of a palette to pick a
+/// random color from, or as `name.N` for the `N`th color of a palette. Palettes declared in the
+/// scene shadow the built in `grayscale`, `primary`, `pastel` and `greens`. Random picks are
+/// seeded with `seed`, so a scene always renders the same.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Scene {
+    #[serde(default = "default_width")]
+    pub width: u32,
+    #[serde(default = "default_height")]
+    pub height: u32,
+    #[serde(default = "default_background")]
+    pub background: String,
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub palettes: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub elements: Vec<Element>,
+}
+
+/// Something drawn in a scene. Positions are relative to the center of the canvas.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Element {
+    Circle {
+        #[serde(default)]
+        x: f32,
+        #[serde(default)]
+        y: f32,
+        radius: f32,
+        #[serde(default)]
+        stroke: StrokeSpec,
+        #[serde(default)]
+        animate: Vec<Binding>,
+    },
+    /// Points spread around a circle, connected by chords.
+    CircularShape {
+        #[serde(default)]
+        x: f32,
+        #[serde(default)]
+        y: f32,
+        radius: f32,
+        #[serde(default = "default_spacing")]
+        spacing: Spacing,
+        /// Counterclockwise rotation in degrees.
+        #[serde(default)]
+        rotation: f32,
+        #[serde(default = "default_pattern")]
+        pattern: ChordPattern,
+        #[serde(default)]
+        border: StrokeSpec,
+        #[serde(default)]
+        chords: StrokeSpec,
+        #[serde(default)]
+        animate: Vec<Binding>,
+    },
+    /// A grid with one randomly chosen diagonal drawn per cell, covering `area` or the canvas.
+    TileField {
+        #[serde(default)]
+        area: Option<Area>,
+        grid: Grid,
+        #[serde(default)]
+        stroke: StrokeSpec,
+        #[serde(default)]
+        animate: Vec<Binding>,
+    },
+    /// Non overlapping circles packed into `area` or the canvas.
+    Packing {
+        #[serde(default)]
+        area: Option<Area>,
+        #[serde(default = "default_min_radius")]
+        min_radius: usize,
+        #[serde(default = "default_max_radius")]
+        max_radius: usize,
+        #[serde(default = "default_count")]
+        count: usize,
+        #[serde(default = "default_attempts")]
+        attempts: usize,
+        #[serde(default)]
+        stroke: StrokeSpec,
+        #[serde(default)]
+        animate: Vec<Binding>,
+    },
+}
+
+/// Stroke of an element, its color being resolved against the palettes of the scene once per
+/// drawn shape.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StrokeSpec {
+    #[serde(default = "default_color")]
+    pub color: String,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+}
+
+impl Default for StrokeSpec {
+    fn default() -> StrokeSpec {
+        StrokeSpec {
+            color: default_color(),
+            weight: default_weight(),
+        }
+    }
+}
+
+/// Rectangle given by its center and size.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Area {
+    #[serde(default)]
+    pub x: f32,
+    #[serde(default)]
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+/// Property of an element an animation binding drives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Property {
+    /// Horizontal offset in pixels.
+    X,
+    /// Vertical offset in pixels.
+    Y,
+    /// Counterclockwise rotation in degrees, each shape turning around its own position.
+    Rotation,
+    /// Size factor, each shape growing around its own position.
+    Scale,
+}
+
+/// Oscillates a property between `min` and `max` in a sine wave, starting at `min`.
+///
+/// Several bindings of the same property add up, scales multiply.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Binding {
+    pub property: Property,
+    /// Duration of one full oscillation in seconds.
+    #[serde(default = "default_period")]
+    pub period: f32,
+    pub min: f32,
+    pub max: f32,
+    /// Fraction of a period the wave is shifted by.
+    #[serde(default)]
+    pub phase: f32,
+}
+
+impl Binding {
+    pub fn value(&self, time: f32) -> f32 {
+        let radian = (time / self.period + self.phase) * TAU;
+
+        self.min + (self.max - self.min) * (0.5 - 0.5 * radian.cos())
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_positive("animation period", self.period)?;
+        if self.property == Property::Scale {
+            ensure_positive("animated scale", self.min)?;
+            ensure_positive("animated scale", self.max)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Scene {
+    /// Reads and validates the scene at `path`, the format being picked by its extension.
+    pub fn load(path: impl AsRef<Path>) -> Result<Scene, Error> {
+        let scene: Scene = format::load(path)?;
+        scene.validate()?;
+
+        Ok(scene)
+    }
+
+    pub fn parse(text: &str, format: Format) -> Result<Scene, Error> {
+        let scene: Scene = format.parse(text)?;
+        scene.validate()?;
+
+        Ok(scene)
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("width", self.width as usize, 1)?;
+        ensure_at_least("height", self.height as usize, 1)?;
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        self.color(&self.background, &mut rng)?;
+
+        for element in &self.elements {
+            self.validate_element(element, &mut rng)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_element<R: Rng>(&self, element: &Element, rng: &mut R) -> Result<(), Error> {
+        let strokes = match element {
+            Element::Circle { radius, stroke, .. } => {
+                ensure_non_negative("circle radius", *radius)?;
+                vec![stroke]
+            }
+            Element::CircularShape {
+                radius,
+                spacing,
+                border,
+                chords,
+                ..
+            } => {
+                ensure_non_negative("circular shape radius", *radius)?;
+                spacing.validate()?;
+                vec![border, chords]
+            }
+            Element::TileField {
+                area, grid, stroke, ..
+            } => {
+                validate_area(area)?;
+                grid.validate()?;
+                vec![stroke]
+            }
+            Element::Packing {
+                area,
+                min_radius,
+                max_radius,
+                attempts,
+                stroke,
+                ..
+            } => {
+                validate_area(area)?;
+                ensure_at_least("max radius", *max_radius, 1)?;
+                if min_radius > max_radius {
+                    return Err(Error::invalid(
+                        "min radius",
+                        format!(
+                            "must not be greater than the max radius, got {} > {}",
+                            min_radius, max_radius
+                        ),
+                    ));
+                }
+                ensure_at_least("packing attempts", *attempts, 1)?;
+                vec![stroke]
+            }
+        };
+
+        for stroke in strokes {
+            ensure_non_negative("stroke weight", stroke.weight)?;
+            self.color(&stroke.color, rng)?;
+        }
+
+        element.animate().iter().try_for_each(Binding::validate)
+    }
+
+    /// Whether any element is animated, i.e. the scene needs to be redrawn over time.
+    pub fn is_animated(&self) -> bool {
+        self.elements.iter().any(|e| !e.animate().is_empty())
+    }
+
+    /// Builds the shapes of all elements within `rect`, the canvas being centered on it.
+    pub fn compose(&self, rect: Rect) -> Result<Composition, Error> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let background = self.color(&self.background, &mut rng)?;
+
+        let layers = self
+            .elements
+            .iter()
+            .map(|element| {
+                Ok(Layer {
+                    shapes: self.shapes(element, rect, &mut rng)?,
+                    animate: element.animate().to_vec(),
+                    applied: Transform::default(),
+                })
+            })
+            .collect::<Result<Vec<Layer>, Error>>()?;
+
+        Ok(Composition { background, layers })
+    }
+
+    fn shapes<R: Rng>(
+        &self,
+        element: &Element,
+        rect: Rect,
+        rng: &mut R,
+    ) -> Result<Vec<Box<dyn Drawable>>, Error> {
+        let origin = rect.xy();
+
+        let shapes: Vec<Box<dyn Drawable>> = match element {
+            Element::Circle {
+                x,
+                y,
+                radius,
+                stroke,
+                ..
+            } => vec![Box::new(
+                Circle::new(*radius)
+                    .with_xy(origin + vec2(*x, *y))
+                    .with_stroke(self.stroke(stroke, rng)?),
+            )],
+            Element::CircularShape {
+                x,
+                y,
+                radius,
+                spacing,
+                rotation,
+                pattern,
+                border,
+                chords,
+                ..
+            } => vec![Box::new(
+                Shape::new(origin + vec2(*x, *y))
+                    .with_radius(*radius)
+                    .with_spacing(spacing.clone())
+                    .with_rotation(deg_to_rad(*rotation))
+                    .with_pattern(pattern.clone())
+                    .with_border_style(self.stroke(border, rng)?)
+                    .with_stroke_style(self.stroke(chords, rng)?),
+            )],
+            Element::TileField {
+                area, grid, stroke, ..
+            } => {
+                let area = area_rect(area, rect);
+                let mut shapes: Vec<Box<dyn Drawable>> = vec![];
+
+                for cell in grid.cells(area, rng) {
+                    let (start, end) = cell.diagonal(rng.gen_range(0..cell.diagonal_count()));
+
+                    shapes.push(Box::new(
+                        Polygon::new(vec![start - cell.center, end - cell.center])
+                            .with_xy(cell.center)
+                            .with_closed(false)
+                            .with_stroke(self.stroke(stroke, rng)?),
+                    ));
+                }
+
+                shapes
+            }
+            Element::Packing {
+                area,
+                min_radius,
+                max_radius,
+                count,
+                attempts,
+                stroke,
+                ..
+            } => {
+                let area = area_rect(area, rect);
+                let mut shapes: Vec<Box<dyn Drawable>> = vec![];
+
+                for circle in pack_circles(&area, *min_radius, *max_radius, *count, *attempts, rng)
+                {
+                    shapes.push(Box::new(circle.with_stroke(self.stroke(stroke, rng)?)));
+                }
+
+                shapes
+            }
+        };
+
+        for shape in &shapes {
+            shape.validate()?;
+        }
+
+        Ok(shapes)
+    }
+
+    fn stroke<R: Rng>(&self, stroke: &StrokeSpec, rng: &mut R) -> Result<StrokeStyle, Error> {
+        Ok(StrokeStyle::new(
+            self.color(&stroke.color, rng)?,
+            stroke.weight,
+        ))
+    }
+
+    /// Resolves a hex color, a palette name or a `name.N` palette entry.
+    fn color<R: Rng>(&self, spec: &str, rng: &mut R) -> Result<Rgba, Error> {
+        let unknown = || {
+            Error::invalid(
+                "color",
+                format!("`{}` is neither a hex color nor a palette", spec),
+            )
+        };
+
+        if spec.starts_with('#') {
+            return color::from_hex(spec).ok_or_else(unknown);
+        }
+
+        let (name, index) = match spec.split_once('.') {
+            Some((name, index)) => (name, Some(index.parse::<usize>().map_err(|_| unknown())?)),
+            None => (spec, None),
+        };
+
+        let colors = match (self.palettes.get(name), Palette::from_name(name)) {
+            (Some(colors), _) => colors
+                .iter()
+                .map(|c| color::from_hex(c).ok_or_else(unknown))
+                .collect::<Result<Vec<Rgba>, Error>>()?,
+            (None, Some(palette)) if index.is_none() => return Ok(palette.pick(rng)),
+            (None, Some(palette)) => palette.colors(),
+            (None, None) => return Err(unknown()),
+        };
+
+        match index {
+            Some(i) => colors.get(i).copied().ok_or_else(|| {
+                Error::invalid("color", format!("palette `{}` has no color {}", name, i))
+            }),
+            None => colors
+                .choose(rng)
+                .copied()
+                .ok_or_else(|| Error::invalid("color", format!("palette `{}` is empty", name))),
+        }
+    }
+}
+
+impl Element {
+    pub fn animate(&self) -> &[Binding] {
+        match self {
+            Element::Circle { animate, .. }
+            | Element::CircularShape { animate, .. }
+            | Element::TileField { animate, .. }
+            | Element::Packing { animate, .. } => animate,
+        }
+    }
+}
+
+/// The shapes of a scene, ready to be drawn and animated.
+pub struct Composition {
+    pub background: Rgba,
+    layers: Vec<Layer>,
+}
+
+impl Composition {
+    /// Moves the animated shapes to where their bindings put them `time` seconds in.
+    pub fn update(&mut self, time: f32) {
+        self.layers.iter_mut().for_each(|l| l.update(time));
+    }
+
+    pub fn draw(&self, canvas: &dyn Canvas) {
+        canvas.fill_background(self.background);
+
+        for layer in &self.layers {
+            layer.shapes.iter().for_each(|s| s.draw(canvas));
+        }
+    }
+}
+
+/// Shapes of one element, along with the animation already applied to them.
+struct Layer {
+    shapes: Vec<Box<dyn Drawable>>,
+    animate: Vec<Binding>,
+    applied: Transform,
+}
+
+impl Layer {
+    fn update(&mut self, time: f32) {
+        if self.animate.is_empty() {
+            return;
+        }
+
+        let target = Transform::at(&self.animate, time);
+        let offset = target.offset - self.applied.offset;
+        let rotation = target.rotation - self.applied.rotation;
+        let scale = target.scale / self.applied.scale;
+
+        // Shapes rotate and scale around their own position, so the order doesn't matter.
+        for shape in self.shapes.iter_mut() {
+            shape.translate(offset);
+            shape.rotate(rotation);
+            shape.scale(scale);
+        }
+
+        self.applied = target;
+    }
+}
+
+struct Transform {
+    offset: Vec2,
+    rotation: f32,
+    scale: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform {
+            offset: vec2(0.0, 0.0),
+            rotation: 0.0,
+            scale: 1.0,
+        }
+    }
+}
+
+impl Transform {
+    fn at(bindings: &[Binding], time: f32) -> Transform {
+        bindings
+            .iter()
+            .fold(Transform::default(), |mut transform, binding| {
+                let value = binding.value(time);
+                match binding.property {
+                    Property::X => transform.offset.x += value,
+                    Property::Y => transform.offset.y += value,
+                    Property::Rotation => transform.rotation += deg_to_rad(value),
+                    Property::Scale => transform.scale *= value,
+                }
+
+                transform
+            })
+    }
+}
+
+fn area_rect(area: &Option<Area>, rect: Rect) -> Rect {
+    match area {
+        Some(area) => Rect::from_x_y_w_h(rect.x() + area.x, rect.y() + area.y, area.w, area.h),
+        None => rect,
+    }
+}
+
+fn validate_area(area: &Option<Area>) -> Result<(), Error> {
+    match area {
+        Some(area) => {
+            ensure_positive("area width", area.w)?;
+            ensure_positive("area height", area.h)
+        }
+        None => Ok(()),
+    }
+}
+
+fn default_width() -> u32 {
+    1024
+}
+
+fn default_height() -> u32 {
+    768
+}
+
+fn default_background() -> String {
+    String::from("#ffffff")
+}
+
+fn default_color() -> String {
+    String::from("#000000")
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+fn default_spacing() -> Spacing {
+    Spacing::Vertices(12)
+}
+
+fn default_pattern() -> ChordPattern {
+    ChordPattern::Diagonals
+}
+
+fn default_min_radius() -> usize {
+    2
+}
+
+fn default_max_radius() -> usize {
+    100
+}
+
+fn default_count() -> usize {
+    500
+}
+
+fn default_attempts() -> usize {
+    200
+}
+
+fn default_period() -> f32 {
+    1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(period: f32, phase: f32) -> Binding {
+        Binding {
+            property: Property::X,
+            period,
+            min: -10.0,
+            max: 30.0,
+            phase,
+        }
+    }
+
+    fn assert_near(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-4, "{} is not {}", a, b);
+    }
+
+    #[test]
+    fn binding_oscillates_between_min_and_max() {
+        let binding = binding(2.0, 0.0);
+
+        assert_near(binding.value(0.0), -10.0);
+        assert_near(binding.value(0.5), 10.0);
+        assert_near(binding.value(1.0), 30.0);
+        assert_near(binding.value(2.0), -10.0);
+        assert_near(binding.value(5.0), 30.0);
+    }
+
+    #[test]
+    fn binding_phase_shifts_by_fractions_of_a_period() {
+        assert_near(binding(2.0, 0.5).value(0.0), 30.0);
+        assert_near(binding(2.0, 0.25).value(0.5), 30.0);
+        assert_near(binding(4.0, 1.0).value(1.0), binding(4.0, 0.0).value(1.0));
+    }
+}
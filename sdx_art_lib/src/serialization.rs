@@ -0,0 +1,111 @@
+//! Serde adapters for the nannou types used in shapes and descriptors, to be used with
+//! `#[serde(with = "...")]`.
+
+/// Colors as `#rrggbb` hex strings, or `#rrggbbaa` if not fully opaque. Channels are rounded
+/// to 8 bits.
+pub mod rgba {
+    use nannou::prelude::*;
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::color::{from_hex, to_hex, to_hex_alpha};
+
+    pub fn serialize<S: Serializer>(color: &Rgba, serializer: S) -> Result<S::Ok, S::Error> {
+        if color.alpha >= 1.0 {
+            serializer.serialize_str(&to_hex(*color))
+        } else {
+            serializer.serialize_str(&to_hex_alpha(*color))
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rgba, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+
+        from_hex(&hex).ok_or_else(|| D::Error::custom(format!("invalid hex color `{}`", hex)))
+    }
+}
+
+/// Optional colors, see [`rgba`].
+pub mod option_rgba {
+    use nannou::prelude::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Hex(#[serde(with = "super::rgba")] Rgba);
+
+    pub fn serialize<S: Serializer>(
+        color: &Option<Rgba>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        color.map(Hex).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Rgba>, D::Error> {
+        Ok(Option::<Hex>::deserialize(deserializer)?.map(|Hex(color)| color))
+    }
+}
+
+/// Vectors and points as `[x, y]`.
+pub mod vec2 {
+    use nannou::prelude::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &Vec2, serializer: S) -> Result<S::Ok, S::Error> {
+        [v.x, v.y].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec2, D::Error> {
+        let [x, y] = <[f32; 2]>::deserialize(deserializer)?;
+
+        Ok(vec2(x, y))
+    }
+}
+
+/// Point lists as `[[x, y], ...]`.
+pub mod vec2_list {
+    use nannou::prelude::*;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(points: &[Vec2], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(points.iter().map(|p| [p.x, p.y]))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Vec2>, D::Error> {
+        let points = Vec::<[f32; 2]>::deserialize(deserializer)?;
+
+        Ok(points.into_iter().map(|[x, y]| vec2(x, y)).collect())
+    }
+}
+
+/// Optional rects as `{ x, y, w, h }`, given by their center and size.
+pub mod option_rect {
+    use nannou::prelude::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct XyWh {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+    }
+
+    pub fn serialize<S: Serializer>(rect: &Option<Rect>, serializer: S) -> Result<S::Ok, S::Error> {
+        rect.map(|r| XyWh {
+            x: r.x(),
+            y: r.y(),
+            w: r.w(),
+            h: r.h(),
+        })
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Rect>, D::Error> {
+        Ok(Option::<XyWh>::deserialize(deserializer)?
+            .map(|XyWh { x, y, w, h }| Rect::from_x_y_w_h(x, y, w, h)))
+    }
+}
@@ -1,11 +1,17 @@
 use nannou::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+use crate::canvas::Canvas;
+use crate::error::{ensure_non_negative, Error};
+use crate::shapes::Drawable;
+use crate::stroke::StrokeStyle;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Circle {
+    #[serde(with = "crate::serialization::vec2")]
     pub pos: Vec2,
     pub radius: f32,
-    pub weight: f32,
-    pub color: Rgba,
+    pub stroke: StrokeStyle,
 }
 
 impl Circle {
@@ -15,8 +21,7 @@ impl Circle {
         Circle {
             pos,
             radius,
-            weight: 1.0,
-            color: Rgba::new(0.0, 0.0, 0.0, 1.0),
+            stroke: StrokeStyle::default(),
         }
     }
 
@@ -43,33 +48,76 @@ impl Circle {
 
     pub fn with_color(&self, color: Rgba) -> Circle {
         Circle {
-            color,
+            stroke: self.stroke.with_color(color),
             ..self.clone()
         }
     }
 
     pub fn with_weight(&self, weight: f32) -> Circle {
         Circle {
-            weight,
+            stroke: self.stroke.with_weight(weight),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_stroke(&self, stroke: StrokeStyle) -> Circle {
+        Circle {
+            stroke,
             ..self.clone()
         }
     }
+
+    /// Stroke weight, which used to be the `weight` field.
+    #[deprecated(since = "0.2.0", note = "use `stroke.weight` instead")]
+    pub fn weight(&self) -> f32 {
+        self.stroke.weight
+    }
+
+    /// Stroke color, which used to be the `color` field.
+    #[deprecated(since = "0.2.0", note = "use `stroke.color` instead")]
+    pub fn color(&self) -> Rgba {
+        self.stroke.color
+    }
 }
 
-impl Circle {
-    pub fn draw(&self, draw: &Draw) {
-        let points = (0..=360).map(|i| {
-            let radian = deg_to_rad(i as f32);
+impl Drawable for Circle {
+    fn draw(&self, canvas: &dyn Canvas) {
+        canvas.stroke_path(&self.to_polyline(), &self.stroke);
+    }
 
-            let x = radian.sin() * self.radius + self.pos.x;
-            let y = radian.cos() * self.radius + self.pos.y;
+    fn to_polyline(&self) -> Vec<Point2> {
+        (0..=360)
+            .map(|i| {
+                let radian = deg_to_rad(i as f32);
 
-            pt2(x, y)
-        });
+                let x = radian.sin() * self.radius + self.pos.x;
+                let y = radian.cos() * self.radius + self.pos.y;
+
+                pt2(x, y)
+            })
+            .collect()
+    }
+
+    fn translate(&mut self, offset: Vec2) {
+        self.pos += offset;
+    }
+
+    fn rotate(&mut self, _radians: f32) {}
+
+    fn scale(&mut self, factor: f32) {
+        self.radius *= factor;
+    }
+
+    fn bounds(&self) -> Rect {
+        Rect::from_xy_wh(self.pos, vec2(self.radius, self.radius) * 2.0)
+    }
+
+    fn contains(&self, point: Point2) -> bool {
+        self.pos.distance(point) <= self.radius
+    }
 
-        draw.polyline()
-            .color(self.color)
-            .weight(self.weight)
-            .points(points);
+    fn validate(&self) -> Result<(), Error> {
+        ensure_non_negative("circle radius", self.radius)?;
+        self.stroke.validate()
     }
 }
@@ -3,8 +3,14 @@ use std::time::{Duration, Instant};
 use nannou::prelude::*;
 use nannou::rand::prelude::SliceRandom;
 use nannou::rand::thread_rng;
+use serde::{Deserialize, Serialize};
 
-use crate::points_list_diagonals;
+use crate::canvas::Canvas;
+use crate::chords::ChordPattern;
+use crate::error::{ensure_at_least, ensure_non_negative, Error};
+use crate::geometry::rotate;
+use crate::shapes::Drawable;
+use crate::stroke::StrokeStyle;
 
 // Divisor of 360:
 // 1 2 3 4 5 6 8 9 10 12 15 18 20 24 30 36 40 45 60 72 90 120 180
@@ -14,24 +20,89 @@ pub const DIVISORS_360: &[usize] = &[
 
 const _FANCY_DIVISORS_360: &[usize] = &[8, 60, 72, 90, 24, 40, 45];
 
+/// How the points of a [`Shape`] are spread around its circle.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Spacing {
+    /// A point every `step` degrees, which may be fractional. Steps not dividing 360 leave a
+    /// shorter gap before the first point. Clamped to 0.1 up to 360 degrees.
+    Degrees(f32),
+    /// `n` evenly spread points, at least two.
+    Vertices(usize),
+    /// Points at the given angles in degrees, measured clockwise from the top.
+    Angles(Vec<f32>),
+}
+
+impl Spacing {
+    /// Angles of the points in degrees, sorted and within 0 up to 360. Never empty.
+    pub fn angles(&self) -> Vec<f32> {
+        let mut angles: Vec<f32> = match self {
+            Spacing::Degrees(step) => {
+                let step = step.clamp(0.1, 360.0);
+                let count = (360.0 / step - 0.001).ceil().max(1.0) as usize;
+
+                (0..count).map(|i| i as f32 * step).collect()
+            }
+            Spacing::Vertices(n) => {
+                let n = (*n).max(2);
+
+                (0..n).map(|i| i as f32 * 360.0 / n as f32).collect()
+            }
+            Spacing::Angles(angles) => angles.iter().map(|a| a.rem_euclid(360.0)).collect(),
+        };
+
+        if angles.is_empty() {
+            angles.push(0.0);
+        }
+        angles.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        angles
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        match self {
+            Spacing::Degrees(step) if *step > 0.0 && *step <= 360.0 => Ok(()),
+            Spacing::Degrees(step) => Err(Error::invalid(
+                "step",
+                format!("must be above 0 and at most 360 degrees, got {}", step),
+            )),
+            Spacing::Vertices(n) => ensure_at_least("vertices", *n, 2),
+            Spacing::Angles(angles) if angles.is_empty() => {
+                Err(Error::invalid("angles", "must not be empty"))
+            }
+            Spacing::Angles(angles) => match angles.iter().find(|a| !a.is_finite()) {
+                Some(angle) => Err(Error::invalid(
+                    "angles",
+                    format!("must be finite, got {}", angle),
+                )),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 struct Radius {
     base: f32,
     current: f32,
     offset: f32,
 }
 
-struct Color {
-    color: Rgba,
-    weight: f32,
-}
-
+#[derive(Serialize, Deserialize)]
 pub struct Shape {
+    #[serde(with = "crate::serialization::vec2")]
     pos: Point2,
     radius: Radius,
-    step: usize,
-    border_color: Color,
-    stroke_color: Color,
+    spacing: Spacing,
+    /// Counterclockwise rotation in radians, unrotated the first point is straight up.
+    rotation: f32,
+    /// Chords drawn between the points on the circle.
+    pattern: ChordPattern,
+    border_color: StrokeStyle,
+    stroke_color: StrokeStyle,
+    #[serde(with = "crate::serialization::option_rect")]
     boundary: Option<Rect>,
+    #[serde(skip, default = "Instant::now")]
     last_update: Instant,
     update_duration: u64,
 }
@@ -45,15 +116,11 @@ impl Shape {
                 current: 100.0,
                 offset: 0.0,
             },
-            step: rng_360_divisor(),
-            border_color: Color {
-                color: Rgba::new(0.8, 0.8, 0.8, 1.0),
-                weight: 2.0,
-            },
-            stroke_color: Color {
-                color: Rgba::new(0.8, 0.8, 0.8, 0.6),
-                weight: 2.0,
-            },
+            spacing: Spacing::Degrees(rng_360_divisor() as f32),
+            rotation: 0.0,
+            pattern: ChordPattern::Diagonals,
+            border_color: StrokeStyle::new(Rgba::new(0.8, 0.8, 0.8, 1.0), 2.0),
+            stroke_color: StrokeStyle::new(Rgba::new(0.8, 0.8, 0.8, 0.6), 2.0),
             boundary: None,
             last_update: Instant::now(),
             update_duration: 500,
@@ -68,7 +135,9 @@ impl Shape {
                 current: radius,
                 offset: self.radius.offset,
             },
-            step: self.step,
+            spacing: self.spacing,
+            rotation: self.rotation,
+            pattern: self.pattern,
             border_color: self.border_color,
             stroke_color: self.stroke_color,
             boundary: None,
@@ -84,7 +153,9 @@ impl Shape {
                 offset: radius_offset,
                 ..self.radius
             },
-            step: self.step,
+            spacing: self.spacing,
+            rotation: self.rotation,
+            pattern: self.pattern,
             border_color: self.border_color,
             stroke_color: self.stroke_color,
             boundary: None,
@@ -94,10 +165,46 @@ impl Shape {
     }
 
     pub fn with_step(self, step: usize) -> Shape {
+        self.with_spacing(Spacing::Degrees(step as f32))
+    }
+
+    pub fn with_spacing(self, spacing: Spacing) -> Shape {
+        Shape {
+            pos: self.pos,
+            radius: self.radius,
+            spacing,
+            rotation: self.rotation,
+            pattern: self.pattern,
+            border_color: self.border_color,
+            stroke_color: self.stroke_color,
+            boundary: None,
+            last_update: self.last_update,
+            update_duration: self.update_duration,
+        }
+    }
+
+    pub fn with_rotation(self, rotation: f32) -> Shape {
+        Shape {
+            pos: self.pos,
+            radius: self.radius,
+            spacing: self.spacing,
+            rotation,
+            pattern: self.pattern,
+            border_color: self.border_color,
+            stroke_color: self.stroke_color,
+            boundary: None,
+            last_update: self.last_update,
+            update_duration: self.update_duration,
+        }
+    }
+
+    pub fn with_pattern(self, pattern: ChordPattern) -> Shape {
         Shape {
             pos: self.pos,
             radius: self.radius,
-            step,
+            spacing: self.spacing,
+            rotation: self.rotation,
+            pattern,
             border_color: self.border_color,
             stroke_color: self.stroke_color,
             boundary: None,
@@ -110,7 +217,9 @@ impl Shape {
         Shape {
             pos: self.pos,
             radius: self.radius,
-            step: self.step,
+            spacing: self.spacing,
+            rotation: self.rotation,
+            pattern: self.pattern,
             border_color: self.border_color,
             stroke_color: self.stroke_color,
             boundary: None,
@@ -123,7 +232,9 @@ impl Shape {
         Shape {
             pos: self.pos,
             radius: self.radius,
-            step: self.step,
+            spacing: self.spacing,
+            rotation: self.rotation,
+            pattern: self.pattern,
             border_color: self.border_color,
             stroke_color: self.stroke_color,
             boundary: Some(boundary),
@@ -136,11 +247,10 @@ impl Shape {
         Shape {
             pos: self.pos,
             radius: self.radius,
-            step: self.step,
-            border_color: Color {
-                color,
-                weight: width,
-            },
+            spacing: self.spacing,
+            rotation: self.rotation,
+            pattern: self.pattern,
+            border_color: self.border_color.with_color(color).with_weight(width),
             stroke_color: self.stroke_color,
             boundary: None,
             last_update: self.last_update,
@@ -152,12 +262,41 @@ impl Shape {
         Shape {
             pos: self.pos,
             radius: self.radius,
-            step: self.step,
+            spacing: self.spacing,
+            rotation: self.rotation,
+            pattern: self.pattern,
             border_color: self.border_color,
-            stroke_color: Color {
-                color,
-                weight: width,
-            },
+            stroke_color: self.stroke_color.with_color(color).with_weight(width),
+            boundary: None,
+            last_update: self.last_update,
+            update_duration: self.update_duration,
+        }
+    }
+
+    pub fn with_border_style(self, style: StrokeStyle) -> Shape {
+        Shape {
+            pos: self.pos,
+            radius: self.radius,
+            spacing: self.spacing,
+            rotation: self.rotation,
+            pattern: self.pattern,
+            border_color: style,
+            stroke_color: self.stroke_color,
+            boundary: None,
+            last_update: self.last_update,
+            update_duration: self.update_duration,
+        }
+    }
+
+    pub fn with_stroke_style(self, style: StrokeStyle) -> Shape {
+        Shape {
+            pos: self.pos,
+            radius: self.radius,
+            spacing: self.spacing,
+            rotation: self.rotation,
+            pattern: self.pattern,
+            border_color: self.border_color,
+            stroke_color: style,
             boundary: None,
             last_update: self.last_update,
             update_duration: self.update_duration,
@@ -179,7 +318,7 @@ impl Shape {
 
     pub fn update_step(&mut self) {
         if self.last_update.elapsed() > Duration::from_millis(self.update_duration) {
-            self.step = rng_360_divisor();
+            self.spacing = Spacing::Degrees(rng_360_divisor() as f32);
             self.last_update = Instant::now();
         }
     }
@@ -308,44 +447,60 @@ impl Shape {
     }
 }
 
-pub fn draw_circular_shape(draw: &Draw, shape: &Shape) {
-    let points = circle_points(shape.radius.current, shape.pos.x, shape.pos.y, shape.step);
-    let diagonal_points = points_list_diagonals(&points);
-    for (start, end) in diagonal_points {
-        draw.line()
-            .start(start)
-            .end(end)
-            .color(shape.stroke_color.color)
-            .weight(shape.border_color.weight);
-    }
-
-    let points_colored = points.iter().map(|p| (*p, shape.border_color.color));
-    draw.polyline()
-        .weight(shape.stroke_color.weight)
-        .points_colored(points_colored);
-}
+impl Drawable for Shape {
+    fn draw(&self, canvas: &dyn Canvas) {
+        let points = self.to_polyline();
+        for (start, end) in self.pattern.chords(&points[..points.len() - 1]) {
+            canvas.stroke_line(start, end, &self.stroke_color);
+        }
 
-fn circle_points(radius: f32, offset_x: f32, offset_y: f32, step: usize) -> Vec<Point2> {
-    if !DIVISORS_360.contains(&step) {
-        let divisors = DIVISORS_360
-            .iter()
-            .map(|d| d.to_string())
-            .collect::<Vec<String>>();
+        canvas.stroke_path(&points, &self.border_color);
+    }
 
-        panic!("step must be one of: {}", divisors.join(", "));
+    fn to_polyline(&self) -> Vec<Point2> {
+        circle_points(self.radius.current, self.pos, self.rotation, &self.spacing)
     }
 
-    (0..=360)
-        .step_by(step)
-        .map(|i| {
-            let radian = deg_to_rad(i as f32);
+    fn translate(&mut self, offset: Vec2) {
+        self.pos += offset;
+    }
 
-            let x = radian.sin() * radius + offset_x;
-            let y = radian.cos() * radius + offset_y;
+    fn rotate(&mut self, radians: f32) {
+        self.rotation += radians;
+    }
+
+    fn scale(&mut self, factor: f32) {
+        self.radius.base *= factor;
+        self.radius.current *= factor;
+        self.radius.offset *= factor;
+    }
 
-            pt2(x, y)
+    fn validate(&self) -> Result<(), Error> {
+        ensure_non_negative("radius", self.radius.base)?;
+        ensure_non_negative("radius offset", self.radius.offset)?;
+        self.spacing.validate()?;
+        self.border_color.validate()?;
+        self.stroke_color.validate()
+    }
+}
+
+/// Points on the circle clockwise from the top, repeating the first point at the end.
+fn circle_points(radius: f32, center: Point2, rotation: f32, spacing: &Spacing) -> Vec<Point2> {
+    let mut points = spacing
+        .angles()
+        .into_iter()
+        .map(|angle| {
+            let radian = deg_to_rad(angle);
+
+            let x = radian.sin() * radius;
+            let y = radian.cos() * radius;
+
+            rotate(pt2(x, y), rotation) + center
         })
-        .collect()
+        .collect::<Vec<Point2>>();
+    points.push(points[0]);
+
+    points
 }
 
 pub fn rng_360_divisor() -> usize {
@@ -1,2 +1,85 @@
+use nannou::prelude::*;
+
+use crate::canvas::Canvas;
+use crate::error::Error;
+use crate::geometry::{bounding_rect, polygon_contains};
+use crate::stroke::StrokeStyle;
+
 pub mod circle;
 pub mod circular;
+pub mod polygon;
+pub mod rectangle;
+pub mod regular_polygon;
+pub mod star;
+
+/// Common interface of all shapes, so differently shaped things can be stored, animated and
+/// exported together, e.g. as a `Vec<Box<dyn Drawable>>`.
+///
+/// Rotation and scaling happen around the position of the shape and leave stroke weights
+/// untouched.
+pub trait Drawable {
+    fn draw(&self, canvas: &dyn Canvas);
+
+    /// Outline of the shape, closed outlines ending with their first point.
+    fn to_polyline(&self) -> Vec<Point2>;
+
+    fn translate(&mut self, offset: Vec2);
+
+    /// Rotates counterclockwise by `radians`.
+    fn rotate(&mut self, radians: f32);
+
+    fn scale(&mut self, factor: f32);
+
+    fn bounds(&self) -> Rect {
+        bounding_rect(&self.to_polyline())
+    }
+
+    fn contains(&self, point: Point2) -> bool {
+        polygon_contains(&self.to_polyline(), point)
+    }
+
+    /// Checks the parameters of the shape, e.g. that sizes aren't negative.
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Fills the outline given by `points` and strokes it on top, returning to the first point if
+/// `closed`.
+pub(crate) fn fill_and_stroke(
+    canvas: &dyn Canvas,
+    points: &[Point2],
+    fill: Option<Rgba>,
+    stroke: Option<&StrokeStyle>,
+    closed: bool,
+) {
+    if points.is_empty() {
+        return;
+    }
+
+    if let Some(fill) = fill {
+        canvas.fill_polygon(points, fill);
+    }
+
+    if let Some(stroke) = stroke {
+        if closed {
+            canvas.stroke_path(&close(points.to_vec()), stroke);
+        } else {
+            canvas.stroke_path(points, stroke);
+        }
+    }
+}
+
+/// Repeats the first point at the end, turning an outline into a closed polyline.
+pub(crate) fn close(mut points: Vec<Point2>) -> Vec<Point2> {
+    if let Some(first) = points.first().copied() {
+        points.push(first);
+    }
+
+    points
+}
+
+/// Validates the optional stroke shared by the fillable shapes.
+pub(crate) fn validate_stroke(stroke: Option<&StrokeStyle>) -> Result<(), Error> {
+    stroke.map_or(Ok(()), StrokeStyle::validate)
+}
@@ -0,0 +1,136 @@
+use nannou::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::canvas::Canvas;
+use crate::error::Error;
+use crate::geometry::rotate;
+use crate::shapes::{close, fill_and_stroke, validate_stroke, Drawable};
+use crate::stroke::StrokeStyle;
+
+/// Arbitrary polygon, its points being relative to `pos`.
+///
+/// Open polygons are stroked without the edge from the last back to the first point.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Polygon {
+    #[serde(with = "crate::serialization::vec2")]
+    pub pos: Vec2,
+    #[serde(with = "crate::serialization::vec2_list")]
+    pub points: Vec<Point2>,
+    pub closed: bool,
+    #[serde(with = "crate::serialization::option_rgba")]
+    pub fill: Option<Rgba>,
+    pub stroke: Option<StrokeStyle>,
+}
+
+impl Polygon {
+    pub fn new(points: Vec<Point2>) -> Polygon {
+        Polygon {
+            pos: vec2(0.0, 0.0),
+            points,
+            closed: true,
+            fill: None,
+            stroke: Some(StrokeStyle::default()),
+        }
+    }
+
+    pub fn with_xy(&self, pos: Vec2) -> Polygon {
+        Polygon {
+            pos,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_x_y(&self, x: f32, y: f32) -> Polygon {
+        Polygon {
+            pos: vec2(x, y),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_closed(&self, closed: bool) -> Polygon {
+        Polygon {
+            closed,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_color(&self, color: Rgba) -> Polygon {
+        Polygon {
+            stroke: Some(self.stroke.clone().unwrap_or_default().with_color(color)),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_weight(&self, weight: f32) -> Polygon {
+        Polygon {
+            stroke: Some(self.stroke.clone().unwrap_or_default().with_weight(weight)),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_fill(&self, fill: Rgba) -> Polygon {
+        Polygon {
+            fill: Some(fill),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_stroke(&self, stroke: StrokeStyle) -> Polygon {
+        Polygon {
+            stroke: Some(stroke),
+            ..self.clone()
+        }
+    }
+
+    pub fn without_stroke(&self) -> Polygon {
+        Polygon {
+            stroke: None,
+            ..self.clone()
+        }
+    }
+
+    /// Points translated to `pos`.
+    pub fn absolute_points(&self) -> Vec<Point2> {
+        self.points.iter().map(|p| *p + self.pos).collect()
+    }
+}
+
+impl Drawable for Polygon {
+    fn draw(&self, canvas: &dyn Canvas) {
+        fill_and_stroke(
+            canvas,
+            &self.absolute_points(),
+            self.fill,
+            self.stroke.as_ref(),
+            self.closed,
+        );
+    }
+
+    fn to_polyline(&self) -> Vec<Point2> {
+        if self.closed {
+            close(self.absolute_points())
+        } else {
+            self.absolute_points()
+        }
+    }
+
+    fn translate(&mut self, offset: Vec2) {
+        self.pos += offset;
+    }
+
+    fn rotate(&mut self, radians: f32) {
+        for point in self.points.iter_mut() {
+            *point = rotate(*point, radians);
+        }
+    }
+
+    fn scale(&mut self, factor: f32) {
+        for point in self.points.iter_mut() {
+            *point *= factor;
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        validate_stroke(self.stroke.as_ref())
+    }
+}
@@ -0,0 +1,177 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use nannou::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::canvas::Canvas;
+use crate::error::{ensure_non_negative, Error};
+use crate::geometry::rotate;
+use crate::shapes::{close, fill_and_stroke, validate_stroke, Drawable};
+use crate::stroke::StrokeStyle;
+
+const CORNER_SEGMENTS: usize = 8;
+
+/// Rectangle centered at `pos`, rotated counterclockwise by `rotation` radians.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Rectangle {
+    #[serde(with = "crate::serialization::vec2")]
+    pub pos: Vec2,
+    pub width: f32,
+    pub height: f32,
+    pub rotation: f32,
+    /// Radius of the rounded corners, sharp corners if zero.
+    pub corner_radius: f32,
+    #[serde(with = "crate::serialization::option_rgba")]
+    pub fill: Option<Rgba>,
+    pub stroke: Option<StrokeStyle>,
+}
+
+impl Rectangle {
+    pub fn new(width: f32, height: f32) -> Rectangle {
+        Rectangle {
+            pos: vec2(0.0, 0.0),
+            width,
+            height,
+            rotation: 0.0,
+            corner_radius: 0.0,
+            fill: None,
+            stroke: Some(StrokeStyle::default()),
+        }
+    }
+
+    pub fn square(size: f32) -> Rectangle {
+        Rectangle::new(size, size)
+    }
+
+    pub fn with_xy(&self, pos: Vec2) -> Rectangle {
+        Rectangle {
+            pos,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_x_y(&self, x: f32, y: f32) -> Rectangle {
+        Rectangle {
+            pos: vec2(x, y),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_rotation(&self, rotation: f32) -> Rectangle {
+        Rectangle {
+            rotation,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_corner_radius(&self, corner_radius: f32) -> Rectangle {
+        Rectangle {
+            corner_radius,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_color(&self, color: Rgba) -> Rectangle {
+        Rectangle {
+            stroke: Some(self.stroke.clone().unwrap_or_default().with_color(color)),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_weight(&self, weight: f32) -> Rectangle {
+        Rectangle {
+            stroke: Some(self.stroke.clone().unwrap_or_default().with_weight(weight)),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_fill(&self, fill: Rgba) -> Rectangle {
+        Rectangle {
+            fill: Some(fill),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_stroke(&self, stroke: StrokeStyle) -> Rectangle {
+        Rectangle {
+            stroke: Some(stroke),
+            ..self.clone()
+        }
+    }
+
+    pub fn without_stroke(&self) -> Rectangle {
+        Rectangle {
+            stroke: None,
+            ..self.clone()
+        }
+    }
+
+    /// Outline in clockwise order, starting at the top left corner. Rounded corners are
+    /// approximated by a few points each.
+    pub fn points(&self) -> Vec<Point2> {
+        let (w, h) = (self.width / 2.0, self.height / 2.0);
+        let corners = [vec2(-w, h), vec2(w, h), vec2(w, -h), vec2(-w, -h)];
+        let radius = self.corner_radius.clamp(0.0, w.abs().min(h.abs()));
+
+        let outline = if radius > 0.0 {
+            corners
+                .iter()
+                .enumerate()
+                .flat_map(|(i, corner)| {
+                    let center = *corner - corner.signum() * radius;
+                    let start = PI - i as f32 * FRAC_PI_2;
+
+                    (0..=CORNER_SEGMENTS).map(move |step| {
+                        let angle = start - step as f32 / CORNER_SEGMENTS as f32 * FRAC_PI_2;
+
+                        center + vec2(angle.cos(), angle.sin()) * radius
+                    })
+                })
+                .collect()
+        } else {
+            corners.to_vec()
+        };
+
+        outline
+            .into_iter()
+            .map(|point| rotate(point, self.rotation) + self.pos)
+            .collect()
+    }
+}
+
+impl Drawable for Rectangle {
+    fn draw(&self, canvas: &dyn Canvas) {
+        fill_and_stroke(
+            canvas,
+            &self.points(),
+            self.fill,
+            self.stroke.as_ref(),
+            true,
+        );
+    }
+
+    fn to_polyline(&self) -> Vec<Point2> {
+        close(self.points())
+    }
+
+    fn translate(&mut self, offset: Vec2) {
+        self.pos += offset;
+    }
+
+    fn rotate(&mut self, radians: f32) {
+        self.rotation += radians;
+    }
+
+    fn scale(&mut self, factor: f32) {
+        self.width *= factor;
+        self.height *= factor;
+        self.corner_radius *= factor;
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        ensure_non_negative("rectangle width", self.width)?;
+        ensure_non_negative("rectangle height", self.height)?;
+        ensure_non_negative("corner radius", self.corner_radius)?;
+        validate_stroke(self.stroke.as_ref())
+    }
+}
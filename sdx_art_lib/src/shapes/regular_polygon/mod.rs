@@ -0,0 +1,147 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use nannou::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::canvas::Canvas;
+use crate::error::{ensure_at_least, ensure_non_negative, Error};
+use crate::shapes::{close, fill_and_stroke, validate_stroke, Drawable};
+use crate::stroke::StrokeStyle;
+
+/// Polygon with `sides` equal sides whose vertices lie on a circle of `radius` around `pos`.
+///
+/// Unrotated the first vertex points straight up, `rotation` turns it counterclockwise.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RegularPolygon {
+    #[serde(with = "crate::serialization::vec2")]
+    pub pos: Vec2,
+    pub sides: usize,
+    pub radius: f32,
+    pub rotation: f32,
+    #[serde(with = "crate::serialization::option_rgba")]
+    pub fill: Option<Rgba>,
+    pub stroke: Option<StrokeStyle>,
+}
+
+impl RegularPolygon {
+    pub fn new(sides: usize, radius: f32) -> RegularPolygon {
+        RegularPolygon {
+            pos: vec2(0.0, 0.0),
+            sides,
+            radius,
+            rotation: 0.0,
+            fill: None,
+            stroke: Some(StrokeStyle::default()),
+        }
+    }
+
+    pub fn with_xy(&self, pos: Vec2) -> RegularPolygon {
+        RegularPolygon {
+            pos,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_x_y(&self, x: f32, y: f32) -> RegularPolygon {
+        RegularPolygon {
+            pos: vec2(x, y),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_radius(&self, radius: f32) -> RegularPolygon {
+        RegularPolygon {
+            radius,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_rotation(&self, rotation: f32) -> RegularPolygon {
+        RegularPolygon {
+            rotation,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_color(&self, color: Rgba) -> RegularPolygon {
+        RegularPolygon {
+            stroke: Some(self.stroke.clone().unwrap_or_default().with_color(color)),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_weight(&self, weight: f32) -> RegularPolygon {
+        RegularPolygon {
+            stroke: Some(self.stroke.clone().unwrap_or_default().with_weight(weight)),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_fill(&self, fill: Rgba) -> RegularPolygon {
+        RegularPolygon {
+            fill: Some(fill),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_stroke(&self, stroke: StrokeStyle) -> RegularPolygon {
+        RegularPolygon {
+            stroke: Some(stroke),
+            ..self.clone()
+        }
+    }
+
+    pub fn without_stroke(&self) -> RegularPolygon {
+        RegularPolygon {
+            stroke: None,
+            ..self.clone()
+        }
+    }
+
+    /// Vertices in clockwise order. Fewer than three sides are raised to three.
+    pub fn points(&self) -> Vec<Point2> {
+        let sides = self.sides.max(3);
+
+        (0..sides)
+            .map(|i| {
+                let angle = FRAC_PI_2 + self.rotation - i as f32 / sides as f32 * TAU;
+
+                self.pos + vec2(angle.cos(), angle.sin()) * self.radius
+            })
+            .collect()
+    }
+}
+
+impl Drawable for RegularPolygon {
+    fn draw(&self, canvas: &dyn Canvas) {
+        fill_and_stroke(
+            canvas,
+            &self.points(),
+            self.fill,
+            self.stroke.as_ref(),
+            true,
+        );
+    }
+
+    fn to_polyline(&self) -> Vec<Point2> {
+        close(self.points())
+    }
+
+    fn translate(&mut self, offset: Vec2) {
+        self.pos += offset;
+    }
+
+    fn rotate(&mut self, radians: f32) {
+        self.rotation += radians;
+    }
+
+    fn scale(&mut self, factor: f32) {
+        self.radius *= factor;
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("polygon sides", self.sides, 3)?;
+        ensure_non_negative("polygon radius", self.radius)?;
+        validate_stroke(self.stroke.as_ref())
+    }
+}
@@ -0,0 +1,150 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use nannou::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::canvas::Canvas;
+use crate::error::{ensure_at_least, ensure_non_negative, Error};
+use crate::shapes::{close, fill_and_stroke, validate_stroke, Drawable};
+use crate::stroke::StrokeStyle;
+
+/// Star with `tips` tips on a circle of `outer_radius` around `pos`, the notches between them
+/// lying on a circle of `inner_radius`.
+///
+/// Unrotated the first tip points straight up, `rotation` turns it counterclockwise.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Star {
+    #[serde(with = "crate::serialization::vec2")]
+    pub pos: Vec2,
+    pub tips: usize,
+    pub outer_radius: f32,
+    pub inner_radius: f32,
+    pub rotation: f32,
+    #[serde(with = "crate::serialization::option_rgba")]
+    pub fill: Option<Rgba>,
+    pub stroke: Option<StrokeStyle>,
+}
+
+impl Star {
+    pub fn new(tips: usize, outer_radius: f32, inner_radius: f32) -> Star {
+        Star {
+            pos: vec2(0.0, 0.0),
+            tips,
+            outer_radius,
+            inner_radius,
+            rotation: 0.0,
+            fill: None,
+            stroke: Some(StrokeStyle::default()),
+        }
+    }
+
+    pub fn with_xy(&self, pos: Vec2) -> Star {
+        Star {
+            pos,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_x_y(&self, x: f32, y: f32) -> Star {
+        Star {
+            pos: vec2(x, y),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_rotation(&self, rotation: f32) -> Star {
+        Star {
+            rotation,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_color(&self, color: Rgba) -> Star {
+        Star {
+            stroke: Some(self.stroke.clone().unwrap_or_default().with_color(color)),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_weight(&self, weight: f32) -> Star {
+        Star {
+            stroke: Some(self.stroke.clone().unwrap_or_default().with_weight(weight)),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_fill(&self, fill: Rgba) -> Star {
+        Star {
+            fill: Some(fill),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_stroke(&self, stroke: StrokeStyle) -> Star {
+        Star {
+            stroke: Some(stroke),
+            ..self.clone()
+        }
+    }
+
+    pub fn without_stroke(&self) -> Star {
+        Star {
+            stroke: None,
+            ..self.clone()
+        }
+    }
+
+    /// Tips and notches alternating in clockwise order. Fewer than two tips are raised to two.
+    pub fn points(&self) -> Vec<Point2> {
+        let tips = self.tips.max(2);
+
+        (0..tips * 2)
+            .map(|i| {
+                let angle = FRAC_PI_2 + self.rotation - i as f32 / tips as f32 * PI;
+                let radius = if i % 2 == 0 {
+                    self.outer_radius
+                } else {
+                    self.inner_radius
+                };
+
+                self.pos + vec2(angle.cos(), angle.sin()) * radius
+            })
+            .collect()
+    }
+}
+
+impl Drawable for Star {
+    fn draw(&self, canvas: &dyn Canvas) {
+        fill_and_stroke(
+            canvas,
+            &self.points(),
+            self.fill,
+            self.stroke.as_ref(),
+            true,
+        );
+    }
+
+    fn to_polyline(&self) -> Vec<Point2> {
+        close(self.points())
+    }
+
+    fn translate(&mut self, offset: Vec2) {
+        self.pos += offset;
+    }
+
+    fn rotate(&mut self, radians: f32) {
+        self.rotation += radians;
+    }
+
+    fn scale(&mut self, factor: f32) {
+        self.outer_radius *= factor;
+        self.inner_radius *= factor;
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("star tips", self.tips, 2)?;
+        ensure_non_negative("star outer radius", self.outer_radius)?;
+        ensure_non_negative("star inner radius", self.inner_radius)?;
+        validate_stroke(self.stroke.as_ref())
+    }
+}
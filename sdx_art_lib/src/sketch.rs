@@ -0,0 +1,76 @@
+use std::fmt::Display;
+use std::sync::{Mutex, PoisonError};
+
+use nannou::prelude::*;
+
+/// Hands a value from `present` over to the model function of a sketch.
+///
+/// nannou builds models with plain functions, which can't capture anything, so sketches park
+/// what they show in a static `Handoff` until the app starts. Unlike `static mut`s, a handoff
+/// also holds values that aren't `Copy`, like descriptors owning vectors or paths.
+pub struct Handoff<T>(Mutex<Option<T>>);
+
+impl<T> Handoff<T> {
+    pub const fn new() -> Handoff<T> {
+        Handoff(Mutex::new(None))
+    }
+
+    /// Replaces the value handed over, `None` clearing it.
+    pub fn put(&self, value: impl Into<Option<T>>) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = value.into();
+    }
+
+    /// Takes the value handed over, if any is left.
+    pub fn take(&self) -> Option<T> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).take()
+    }
+}
+
+impl<T> Default for Handoff<T> {
+    fn default() -> Handoff<T> {
+        Handoff::new()
+    }
+}
+
+/// Where sketches report what goes wrong while they run, and what they've saved.
+///
+/// nannou runs sketches until the process ends, so nothing can be handed back to the caller
+/// of `present` once the app has started.
+static REPORTER: Mutex<fn(&str)> = Mutex::new(print_to_stderr);
+
+/// Replaces how sketches report, e.g. to log reports rather than print them.
+pub fn set_reporter(reporter: fn(&str)) {
+    *REPORTER.lock().unwrap_or_else(PoisonError::into_inner) = reporter;
+}
+
+/// Reports `message` through the reporter set, printing it to stderr by default.
+pub fn report(message: impl Display) {
+    let reporter = *REPORTER.lock().unwrap_or_else(PoisonError::into_inner);
+    reporter(&message.to_string());
+}
+
+fn print_to_stderr(message: &str) {
+    eprintln!("{}", message);
+}
+
+/// Unwraps the newly built window of a sketch, or reports why it couldn't be created and quits
+/// the app.
+pub fn window<E: Display>(app: &App, built: Result<window::Id, E>) -> Option<window::Id> {
+    built
+        .map_err(|e| {
+            report(format_args!(
+                "Error: failed to create the sketch window: {}",
+                e
+            ));
+            app.quit();
+        })
+        .ok()
+}
+
+/// Renders `draw` to `frame`, reporting failures instead of panicking so the next frame gets a
+/// chance to render.
+pub fn show(app: &App, draw: &Draw, frame: &Frame) {
+    if let Err(e) = draw.to_frame(app, frame) {
+        report(format_args!("Failed to render the frame: {}", e));
+    }
+}
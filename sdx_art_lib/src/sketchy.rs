@@ -0,0 +1,172 @@
+use std::cell::RefCell;
+
+use nannou::prelude::*;
+use nannou::rand::rngs::StdRng;
+use nannou::rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::canvas::Canvas;
+use crate::error::{ensure_at_least, ensure_non_negative, ensure_positive, Error};
+use crate::stroke::StrokeStyle;
+
+/// Parameters of the hand drawn look.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Sketchy {
+    pub seed: u64,
+    /// How many overlapping strokes are drawn per line.
+    pub passes: usize,
+    /// Maximum distance a point is moved off the line.
+    pub wobble: f32,
+    /// Maximum distance open lines are extended beyond their endpoints.
+    pub overshoot: f32,
+    /// Lines are split into segments of about this length so they can wobble in between.
+    pub segment_length: f32,
+}
+
+impl Sketchy {
+    pub fn new(seed: u64) -> Sketchy {
+        Sketchy {
+            seed,
+            passes: 2,
+            wobble: 1.5,
+            overshoot: 4.0,
+            segment_length: 20.0,
+        }
+    }
+
+    pub fn with_passes(&self, passes: usize) -> Sketchy {
+        Sketchy { passes, ..*self }
+    }
+
+    pub fn with_wobble(&self, wobble: f32) -> Sketchy {
+        Sketchy { wobble, ..*self }
+    }
+
+    pub fn with_overshoot(&self, overshoot: f32) -> Sketchy {
+        Sketchy { overshoot, ..*self }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_at_least("sketchy passes", self.passes, 1)?;
+        ensure_non_negative("sketchy wobble", self.wobble)?;
+        ensure_non_negative("sketchy overshoot", self.overshoot)?;
+        ensure_positive("sketchy segment length", self.segment_length)
+    }
+
+    /// Runs `draw` on `canvas` wrapped in a `SketchyCanvas` if `sketchy` is set, or on `canvas`
+    /// itself otherwise.
+    pub fn wrap<R>(
+        sketchy: Option<Sketchy>,
+        canvas: &dyn Canvas,
+        draw: impl FnOnce(&dyn Canvas) -> R,
+    ) -> R {
+        match sketchy {
+            Some(sketchy) => draw(&SketchyCanvas::new(canvas, sketchy)),
+            None => draw(canvas),
+        }
+    }
+}
+
+/// Canvas wrapper drawing every stroke as several slightly off, overshooting pencil lines.
+///
+/// The perturbation is derived from the seed only, so drawing the same things in the same
+/// order always results in the same picture.
+pub struct SketchyCanvas<'a> {
+    canvas: &'a dyn Canvas,
+    sketchy: Sketchy,
+    rng: RefCell<StdRng>,
+}
+
+impl<'a> SketchyCanvas<'a> {
+    pub fn new(canvas: &'a dyn Canvas, sketchy: Sketchy) -> SketchyCanvas<'a> {
+        SketchyCanvas {
+            canvas,
+            sketchy,
+            rng: RefCell::new(StdRng::seed_from_u64(sketchy.seed)),
+        }
+    }
+
+    fn perturb(&self, points: &[Point2]) -> Vec<Point2> {
+        let mut rng = self.rng.borrow_mut();
+        let Sketchy {
+            wobble,
+            overshoot,
+            segment_length,
+            ..
+        } = self.sketchy;
+
+        let closed = points.len() > 2 && points[0].distance(points[points.len() - 1]) < 0.001;
+
+        let mut resampled = vec![points[0]];
+        for segment in points.windows(2) {
+            let steps = (segment[0].distance(segment[1]) / segment_length.max(1.0)).ceil() as usize;
+            for step in 1..=steps.max(1) {
+                resampled.push(segment[0].lerp(segment[1], step as f32 / steps.max(1) as f32));
+            }
+        }
+
+        if !closed && resampled.len() > 1 {
+            let len = resampled.len();
+            let start = (resampled[0] - resampled[1]).normalize_or_zero();
+            let end = (resampled[len - 1] - resampled[len - 2]).normalize_or_zero();
+            resampled[0] += start * rng.gen_range(0.0..=overshoot);
+            resampled[len - 1] += end * rng.gen_range(0.0..=overshoot);
+        }
+
+        let mut perturbed = resampled
+            .into_iter()
+            .map(|p| {
+                p + vec2(
+                    rng.gen_range(-wobble..=wobble),
+                    rng.gen_range(-wobble..=wobble),
+                )
+            })
+            .collect::<Vec<Point2>>();
+
+        if closed {
+            let last = perturbed.len() - 1;
+            perturbed[last] = perturbed[0];
+        }
+
+        perturbed
+    }
+}
+
+impl<'a> Canvas for SketchyCanvas<'a> {
+    fn fill_background(&self, color: Rgba) {
+        self.canvas.fill_background(color);
+    }
+
+    /// Text is drawn as is, wobbly glyphs being hard to read.
+    fn fill_text(&self, text: &str, rect: Rect, size: f32, color: Rgba) {
+        self.canvas.fill_text(text, rect, size, color);
+    }
+
+    fn fill_polygon(&self, points: &[Point2], color: Rgba) {
+        if points.len() < 3 {
+            return;
+        }
+
+        self.canvas.fill_polygon(&self.perturb(points), color);
+    }
+
+    fn stroke_polyline(&self, points: &[Point2], style: &StrokeStyle) {
+        if points.len() < 2 {
+            return;
+        }
+
+        for _ in 0..self.sketchy.passes.max(1) {
+            self.canvas.stroke_polyline(&self.perturb(points), style);
+        }
+    }
+
+    fn stroke_path(&self, points: &[Point2], style: &StrokeStyle) {
+        if points.len() < 2 {
+            return;
+        }
+
+        for _ in 0..self.sketchy.passes.max(1) {
+            self.canvas.stroke_path(&self.perturb(points), style);
+        }
+    }
+}
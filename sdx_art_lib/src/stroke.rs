@@ -0,0 +1,286 @@
+use nannou::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ensure_non_negative, Error};
+
+/// Shape of the open ends of a stroke.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Cap {
+    Butt,
+    Round,
+    Square,
+}
+
+/// Shape of the corners between two stroke segments.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Join {
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StrokeStyle {
+    #[serde(with = "crate::serialization::rgba")]
+    pub color: Rgba,
+    pub weight: f32,
+    pub cap: Cap,
+    pub join: Join,
+    /// Alternating dash and gap lengths, solid if empty.
+    #[serde(default)]
+    pub dash: Vec<f32>,
+    /// Width factors applied to `weight`, spread evenly along the stroke and linearly
+    /// interpolated in between. Uniform width if empty.
+    #[serde(default)]
+    pub widths: Vec<f32>,
+}
+
+impl StrokeStyle {
+    pub fn new(color: Rgba, weight: f32) -> StrokeStyle {
+        StrokeStyle {
+            color,
+            weight,
+            cap: Cap::Butt,
+            join: Join::Miter,
+            dash: vec![],
+            widths: vec![],
+        }
+    }
+
+    pub fn with_color(&self, color: Rgba) -> StrokeStyle {
+        StrokeStyle {
+            color,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_weight(&self, weight: f32) -> StrokeStyle {
+        StrokeStyle {
+            weight,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_cap(&self, cap: Cap) -> StrokeStyle {
+        StrokeStyle {
+            cap,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_join(&self, join: Join) -> StrokeStyle {
+        StrokeStyle {
+            join,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_dash(&self, dash: Vec<f32>) -> StrokeStyle {
+        StrokeStyle {
+            dash,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_widths(&self, widths: Vec<f32>) -> StrokeStyle {
+        StrokeStyle {
+            widths,
+            ..self.clone()
+        }
+    }
+
+    /// Tapers the stroke from nothing to full weight and back, like a brush stroke.
+    pub fn tapered(&self) -> StrokeStyle {
+        self.with_widths(vec![0.0, 1.0, 0.0])
+    }
+
+    pub fn is_dashed(&self) -> bool {
+        self.dash.iter().sum::<f32>() > 0.0
+    }
+
+    pub fn is_variable_width(&self) -> bool {
+        !self.widths.is_empty()
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure_non_negative("stroke weight", self.weight)?;
+        for length in &self.dash {
+            ensure_non_negative("dash length", *length)?;
+        }
+        for width in &self.widths {
+            ensure_non_negative("stroke width factor", *width)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        StrokeStyle::new(Rgba::new(0.0, 0.0, 0.0, 1.0), 1.0)
+    }
+}
+
+/// Splits a polyline into the dashes of the `dash` pattern, alternating dash and gap lengths.
+///
+/// As in SVG, patterns of odd length are repeated to make them even, so `[5]` is `[5, 5]`.
+pub fn dashes(points: &[Point2], dash: &[f32]) -> Vec<Vec<Point2>> {
+    if points.len() < 2 || dash.iter().sum::<f32>() <= 0.0 {
+        return vec![points.to_vec()];
+    }
+
+    let dash = if dash.len() % 2 == 1 {
+        [dash, dash].concat()
+    } else {
+        dash.to_vec()
+    };
+
+    let mut dashes = vec![];
+    let mut current = vec![points[0]];
+    let mut index = 0;
+    let mut remaining = dash[0];
+
+    for segment in points.windows(2) {
+        let (mut start, end) = (segment[0], segment[1]);
+
+        loop {
+            let length = start.distance(end);
+            if remaining >= length {
+                remaining -= length;
+                if index % 2 == 0 {
+                    current.push(end);
+                }
+                break;
+            }
+
+            let split = start.lerp(end, remaining / length.max(f32::EPSILON));
+            if index % 2 == 0 {
+                current.push(split);
+                dashes.push(std::mem::take(&mut current));
+            } else {
+                current = vec![split];
+            }
+
+            start = split;
+            index = (index + 1) % dash.len();
+            remaining = dash[index];
+        }
+    }
+
+    if index % 2 == 0 && current.len() > 1 {
+        dashes.push(current);
+    }
+
+    dashes
+}
+
+/// Outline of a polyline drawn with the per vertex widths of `style`, ready to be filled.
+pub fn variable_width_outline(points: &[Point2], style: &StrokeStyle) -> Vec<Point2> {
+    if points.len() < 2 {
+        return vec![];
+    }
+
+    let lengths = points
+        .windows(2)
+        .scan(0.0, |total, segment| {
+            *total += segment[0].distance(segment[1]);
+            Some(*total)
+        })
+        .collect::<Vec<f32>>();
+    let total = lengths.last().copied().unwrap_or(0.0).max(f32::EPSILON);
+
+    let mut left = vec![];
+    let mut right = vec![];
+
+    for (i, point) in points.iter().enumerate() {
+        let before = if i > 0 {
+            *point - points[i - 1]
+        } else {
+            points[1] - points[0]
+        };
+        let after = if i + 1 < points.len() {
+            points[i + 1] - *point
+        } else {
+            before
+        };
+        let normal = (before.normalize_or_zero() + after.normalize_or_zero())
+            .normalize_or_zero()
+            .perp();
+
+        let progress = if i == 0 { 0.0 } else { lengths[i - 1] / total };
+        let half_width = width_at(&style.widths, progress) * style.weight / 2.0;
+
+        left.push(*point + normal * half_width);
+        right.push(*point - normal * half_width);
+    }
+
+    left.extend(right.into_iter().rev());
+
+    left
+}
+
+fn width_at(widths: &[f32], progress: f32) -> f32 {
+    match widths.len() {
+        0 => 1.0,
+        1 => widths[0],
+        len => {
+            let position = progress.clamp(0.0, 1.0) * (len - 1) as f32;
+            let i = (position.floor() as usize).min(len - 2);
+
+            widths[i] + (widths[i + 1] - widths[i]) * (position - i as f32)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(length: f32) -> Vec<Point2> {
+        vec![pt2(0.0, 0.0), pt2(length, 0.0)]
+    }
+
+    fn spans(dashes: &[Vec<Point2>]) -> Vec<(f32, f32)> {
+        dashes
+            .iter()
+            .map(|dash| (dash[0].x, dash[dash.len() - 1].x))
+            .collect()
+    }
+
+    #[test]
+    fn dashes_even_patterns() {
+        let dashes = dashes(&line(10.0), &[3.0, 1.0]);
+        assert_eq!(spans(&dashes), [(0.0, 3.0), (4.0, 7.0), (8.0, 10.0)]);
+    }
+
+    #[test]
+    fn dashes_repeat_single_lengths() {
+        let dashes = dashes(&line(20.0), &[5.0]);
+        assert_eq!(spans(&dashes), [(0.0, 5.0), (10.0, 15.0)]);
+    }
+
+    #[test]
+    fn dashes_repeat_odd_patterns() {
+        // [5, 3, 2] is [5, 3, 2, 5, 3, 2]: dashes of 5, 2 and 3 with gaps of 3, 5 and 2.
+        let dashes = dashes(&line(30.0), &[5.0, 3.0, 2.0]);
+        assert_eq!(
+            spans(&dashes),
+            [
+                (0.0, 5.0),
+                (8.0, 10.0),
+                (15.0, 18.0),
+                (20.0, 25.0),
+                (28.0, 30.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn dashes_split_across_vertices() {
+        let points = [pt2(0.0, 0.0), pt2(2.0, 0.0), pt2(2.0, 2.0)];
+        let dashes = dashes(&points, &[3.0, 10.0]);
+        assert_eq!(dashes, [vec![pt2(0.0, 0.0), pt2(2.0, 0.0), pt2(2.0, 1.0)]]);
+    }
+}
@@ -0,0 +1,193 @@
+use nannou::prelude::*;
+
+/// Recursively splits `rect` into four equal quarters as long as `split` returns true for the
+/// current rect and its depth and `max_depth` is not reached.
+///
+/// Returns the leaf rects together with the depth they ended up at, the initial rect being depth 0.
+pub fn quadtree<F>(rect: Rect, max_depth: usize, split: &mut F) -> Vec<(Rect, usize)>
+where
+    F: FnMut(&Rect, usize) -> bool,
+{
+    let mut leaves = vec![];
+    quadtree_into(rect, 0, max_depth, split, &mut leaves);
+
+    leaves
+}
+
+fn quadtree_into<F>(
+    rect: Rect,
+    depth: usize,
+    max_depth: usize,
+    split: &mut F,
+    leaves: &mut Vec<(Rect, usize)>,
+) where
+    F: FnMut(&Rect, usize) -> bool,
+{
+    if depth >= max_depth || !split(&rect, depth) {
+        leaves.push((rect, depth));
+        return;
+    }
+
+    let w = rect.w() / 2.0;
+    let h = rect.h() / 2.0;
+    let quarters = [
+        Rect::from_x_y_w_h(rect.x() - w / 2.0, rect.y() + h / 2.0, w, h),
+        Rect::from_x_y_w_h(rect.x() + w / 2.0, rect.y() + h / 2.0, w, h),
+        Rect::from_x_y_w_h(rect.x() + w / 2.0, rect.y() - h / 2.0, w, h),
+        Rect::from_x_y_w_h(rect.x() - w / 2.0, rect.y() - h / 2.0, w, h),
+    ];
+
+    for quarter in quarters {
+        quadtree_into(quarter, depth + 1, max_depth, split, leaves);
+    }
+}
+
+/// Rects shrinking from `rect` in `steps` equal steps down to `min_size` on the shorter side,
+/// the outermost first.
+///
+/// Every step drifts a bit towards `direction`, whose components are usually -1, 0 or 1. With
+/// an `offset` of 1 the rects touch the edges of `rect` on that side, with 0 they stay centered.
+pub fn nested_rects(
+    rect: Rect,
+    steps: usize,
+    min_size: f32,
+    direction: Vec2,
+    offset: f32,
+) -> Vec<Rect> {
+    let size = rect.w().min(rect.h());
+    if size <= 0.0 {
+        return vec![];
+    }
+
+    let min_scale = (min_size / size).clamp(0.0, 1.0);
+    let steps = steps.max(1);
+    // Every step shrinks the rect by the same amount and drifts it by half of that.
+    let shrink = rect.wh() * (1.0 - min_scale) / steps as f32;
+    let drift = shrink / 2.0 * direction * offset;
+
+    let mut rects = Vec::with_capacity(steps + 1);
+    nested_rects_into(rect, shrink, drift, steps, &mut rects);
+
+    rects
+}
+
+fn nested_rects_into(rect: Rect, shrink: Vec2, drift: Vec2, steps: usize, rects: &mut Vec<Rect>) {
+    rects.push(rect);
+    if steps == 0 {
+        return;
+    }
+
+    let inner = Rect::from_xy_wh(rect.xy() + drift, rect.wh() - shrink);
+    nested_rects_into(inner, shrink, drift, steps - 1, rects);
+}
+
+/// Orientation of a line splitting a rect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    /// A line of constant x, splitting into a left and a right part.
+    Vertical,
+    /// A line of constant y, splitting into a top and a bottom part.
+    Horizontal,
+}
+
+/// Binary space partition of a rect, each node being either a leaf or split in two by a line.
+#[derive(Clone, Debug)]
+pub struct Bsp {
+    rect: Rect,
+    split: Option<(Axis, f32, Box<[Bsp; 2]>)>,
+}
+
+impl Bsp {
+    pub fn new(rect: Rect) -> Bsp {
+        Bsp { rect, split: None }
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.split.is_none()
+    }
+
+    /// Splits the leaf at `position` along `axis`, a no-op for inner nodes or if the line does
+    /// not cross the rect. Returns whether the leaf was split.
+    pub fn split(&mut self, axis: Axis, position: f32) -> bool {
+        if !self.is_leaf() || !self.crosses(axis, position) {
+            return false;
+        }
+
+        let r = self.rect;
+        let children = match axis {
+            Axis::Vertical => [
+                Rect::from_corners(r.bottom_left(), pt2(position, r.top())),
+                Rect::from_corners(pt2(position, r.bottom()), r.top_right()),
+            ],
+            Axis::Horizontal => [
+                Rect::from_corners(pt2(r.left(), position), r.top_right()),
+                Rect::from_corners(r.bottom_left(), pt2(r.right(), position)),
+            ],
+        };
+
+        self.split = Some((
+            axis,
+            position,
+            Box::new([Bsp::new(children[0]), Bsp::new(children[1])]),
+        ));
+
+        true
+    }
+
+    /// Splits every leaf crossed by the line at `position` along `axis` for which `split`
+    /// returns true.
+    pub fn split_crossing<F>(&mut self, axis: Axis, position: f32, split: &mut F)
+    where
+        F: FnMut(&Rect) -> bool,
+    {
+        match &mut self.split {
+            Some((_, _, children)) => {
+                for child in children.iter_mut() {
+                    child.split_crossing(axis, position, split);
+                }
+            }
+            None => {
+                if self.crosses(axis, position) && split(&self.rect) {
+                    self.split(axis, position);
+                }
+            }
+        }
+    }
+
+    fn crosses(&self, axis: Axis, position: f32) -> bool {
+        match axis {
+            Axis::Vertical => position > self.rect.left() && position < self.rect.right(),
+            Axis::Horizontal => position > self.rect.bottom() && position < self.rect.top(),
+        }
+    }
+
+    /// The rects of all leaves, left or top children first.
+    pub fn leaves(&self) -> Vec<Rect> {
+        match &self.split {
+            Some((_, _, children)) => children.iter().flat_map(Bsp::leaves).collect(),
+            None => vec![self.rect],
+        }
+    }
+
+    /// Start and end points of all split lines, outer ones first.
+    pub fn lines(&self) -> Vec<(Point2, Point2)> {
+        match &self.split {
+            Some((axis, position, children)) => {
+                let r = self.rect;
+                let line = match axis {
+                    Axis::Vertical => (pt2(*position, r.bottom()), pt2(*position, r.top())),
+                    Axis::Horizontal => (pt2(r.left(), *position), pt2(r.right(), *position)),
+                };
+
+                std::iter::once(line)
+                    .chain(children.iter().flat_map(Bsp::lines))
+                    .collect()
+            }
+            None => vec![],
+        }
+    }
+}
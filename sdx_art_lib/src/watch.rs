@@ -0,0 +1,115 @@
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::format;
+use crate::sketch;
+
+/// How often the file is checked for changes at most.
+const INTERVAL: Duration = Duration::from_millis(250);
+
+/// A descriptor or scene file read again whenever it's saved, so sketches can be tweaked in an
+/// editor while they run.
+///
+/// Changes are noticed by polling the modification time, so sketches need to keep updating
+/// while watching.
+pub struct Watch {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    checked: Instant,
+}
+
+impl Watch {
+    /// Watches `path` for changes from now on.
+    pub fn new(path: impl Into<PathBuf>) -> Watch {
+        let path = path.into();
+
+        Watch {
+            modified: modified(&path),
+            path,
+            checked: Instant::now(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the file was modified since the last change was noticed.
+    pub fn changed(&mut self) -> bool {
+        if self.checked.elapsed() < INTERVAL {
+            return false;
+        }
+        self.checked = Instant::now();
+
+        let modified = modified(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+
+        true
+    }
+
+    /// Reads the file again if it changed, the format being picked by its extension.
+    ///
+    /// Files that fail to load or to `validate` are reported once per change, so sketches can
+    /// keep showing what they had until the file is fixed.
+    pub fn reload<T: DeserializeOwned>(
+        &mut self,
+        validate: impl FnOnce(&T) -> Result<(), Error>,
+    ) -> Option<Result<T, Error>> {
+        if !self.changed() {
+            return None;
+        }
+
+        Some(format::load(&self.path).and_then(|value| validate(&value).map(|_| value)))
+    }
+
+    /// Like `reload`, reporting failures through `sketch::report` instead of returning them.
+    pub fn reload_or_report<T: DeserializeOwned>(
+        &mut self,
+        validate: impl FnOnce(&T) -> Result<(), Error>,
+    ) -> Option<T> {
+        match self.reload(validate)? {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.report(e);
+                None
+            }
+        }
+    }
+
+    /// Reports that the file failed to reload because of `error`.
+    pub fn report(&self, error: impl Display) {
+        sketch::report(format_args!(
+            "Failed to reload {}: {}",
+            self.path.display(),
+            error
+        ));
+    }
+}
+
+/// Reads a file to watch, writing `initial` to it first if it doesn't exist yet so there's
+/// something to edit.
+pub fn load_or_create<T: Serialize + DeserializeOwned>(
+    path: &Path,
+    initial: T,
+) -> Result<T, Error> {
+    if path.exists() {
+        format::load(path)
+    } else {
+        format::save(path, &initial)?;
+        Ok(initial)
+    }
+}
+
+/// Modification time of `path`, if it can be told.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}